@@ -0,0 +1,99 @@
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+
+/// Index of an [`AbilityDef`] in the [`AbilityBook`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Reflect)]
+pub struct AbilityId(pub usize);
+
+/// What an ability does once it has been cast. Adding a variant here and an
+/// apply system for it is all a new ability needs.
+#[derive(Debug, Copy, Clone, PartialEq, Reflect)]
+pub enum AbilityEffect {
+    /// Spawns a new spark at the cast origin.
+    SpawnSpark,
+    /// Throws a lightning ball from the cast origin towards the target.
+    ThrowLightningBall { speed: f32, lifetime_secs: f32 },
+    /// Slows everything that moves inside a sphere around the target.
+    SlowField {
+        radius: f32,
+        speed_multiplier: f32,
+        duration_secs: f32,
+    },
+}
+
+impl AbilityEffect {
+    /// Whether the ability needs a point on the ground to be cast.
+    pub fn needs_target(&self) -> bool {
+        match self {
+            Self::SpawnSpark => false,
+            Self::ThrowLightningBall { .. } | Self::SlowField { .. } => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Reflect)]
+pub struct AbilityDef {
+    pub name: String,
+    pub mana_cost: f32,
+    pub cooldown_secs: f32,
+    pub hotkey: KeyCode,
+    pub effect: AbilityEffect,
+}
+
+/// Every ability that can be cast.
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct AbilityBook(pub Vec<AbilityDef>);
+
+impl Default for AbilityBook {
+    fn default() -> Self {
+        Self(vec![
+            AbilityDef {
+                name: "Spark".to_string(),
+                mana_cost: 20.0,
+                cooldown_secs: 2.0,
+                hotkey: KeyCode::Digit1,
+                effect: AbilityEffect::SpawnSpark,
+            },
+            AbilityDef {
+                name: "Ball".to_string(),
+                mana_cost: 40.0,
+                cooldown_secs: 6.0,
+                hotkey: KeyCode::Digit2,
+                effect: AbilityEffect::ThrowLightningBall {
+                    speed: 150.0,
+                    lifetime_secs: 4.0,
+                },
+            },
+            AbilityDef {
+                name: "Slow".to_string(),
+                mana_cost: 30.0,
+                cooldown_secs: 10.0,
+                hotkey: KeyCode::Digit3,
+                effect: AbilityEffect::SlowField {
+                    radius: 80.0,
+                    speed_multiplier: 0.4,
+                    duration_secs: 5.0,
+                },
+            },
+        ])
+    }
+}
+
+impl AbilityBook {
+    pub fn get(&self, id: AbilityId) -> Option<&AbilityDef> {
+        self.0.get(id.0)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (AbilityId, &AbilityDef)> {
+        self.0
+            .iter()
+            .enumerate()
+            .map(|(ix, def)| (AbilityId(ix), def))
+    }
+}
+
+#[auto_plugin(app=app)]
+pub(super) fn plugin(app: &mut App) {}
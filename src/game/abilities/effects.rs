@@ -0,0 +1,134 @@
+use avian3d::prelude::LinearVelocity;
+use bevy::color::palettes::css::MEDIUM_PURPLE;
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+
+use super::{AbilityCast, AbilitySystems};
+use crate::game::abilities::book::AbilityEffect;
+use crate::game::behaviors::{MovementSpeed, SpeedMultiplier};
+use crate::game::despawn::DespawnAfter;
+use crate::game::effects::lightning_ball::LightningBall;
+use crate::game::pause_controller::PausableSystems;
+use crate::game::screens::Screen;
+use crate::game::spark::Spark;
+
+/// Slows everything with [`MovementSpeed`] inside `radius`.
+#[auto_register_type]
+#[auto_name]
+#[derive(Component, Debug, Copy, Clone, Reflect)]
+#[reflect(Component)]
+#[require(Transform)]
+pub struct SlowField {
+    pub radius: f32,
+    pub speed_multiplier: f32,
+}
+
+#[auto_plugin(app=app)]
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (spawn_spark, throw_lightning_ball, spawn_slow_field)
+            .in_set(AbilitySystems::Apply)
+            .in_set(PausableSystems),
+    );
+    app.add_systems(
+        Update,
+        (apply_slow_fields, draw_slow_fields).in_set(PausableSystems),
+    );
+}
+
+fn spawn_spark(mut casts: EventReader<AbilityCast>, mut commands: Commands) {
+    for cast in casts.read() {
+        let AbilityEffect::SpawnSpark = cast.effect else {
+            continue;
+        };
+        commands.spawn((
+            Spark,
+            Transform::from_translation(cast.origin),
+            StateScoped(Screen::Gameplay),
+        ));
+    }
+}
+
+fn throw_lightning_ball(mut casts: EventReader<AbilityCast>, mut commands: Commands) {
+    for cast in casts.read() {
+        let AbilityEffect::ThrowLightningBall {
+            speed,
+            lifetime_secs,
+        } = cast.effect
+        else {
+            continue;
+        };
+        let Some(target) = cast.target else {
+            continue;
+        };
+        let direction = (target - cast.origin).normalize_or_zero();
+        commands.spawn((
+            LightningBall,
+            Transform::from_translation(cast.origin),
+            LinearVelocity(direction * speed),
+            DespawnAfter::secs(lifetime_secs),
+            StateScoped(Screen::Gameplay),
+        ));
+    }
+}
+
+fn spawn_slow_field(mut casts: EventReader<AbilityCast>, mut commands: Commands) {
+    for cast in casts.read() {
+        let AbilityEffect::SlowField {
+            radius,
+            speed_multiplier,
+            duration_secs,
+        } = cast.effect
+        else {
+            continue;
+        };
+        let Some(target) = cast.target else {
+            continue;
+        };
+        commands.spawn((
+            SlowField {
+                radius,
+                speed_multiplier,
+            },
+            Transform::from_translation(target),
+            DespawnAfter::secs(duration_secs),
+            StateScoped(Screen::Gameplay),
+        ));
+    }
+}
+
+fn apply_slow_fields(
+    mut commands: Commands,
+    fields: Query<(&SlowField, &GlobalTransform)>,
+    movers: Query<(Entity, &GlobalTransform, Has<SpeedMultiplier>), With<MovementSpeed>>,
+) {
+    for (mover, mover_gt, slowed) in movers.iter() {
+        let multiplier = fields
+            .iter()
+            .filter(|(field, field_gt)| {
+                field_gt.translation().distance(mover_gt.translation()) <= field.radius
+            })
+            .map(|(field, _)| field.speed_multiplier)
+            .reduce(f32::min);
+        match multiplier {
+            Some(multiplier) => {
+                commands.entity(mover).insert(SpeedMultiplier(multiplier));
+            }
+            None if slowed => {
+                commands.entity(mover).remove::<SpeedMultiplier>();
+            }
+            None => {}
+        }
+    }
+}
+
+fn draw_slow_fields(mut gizmos: Gizmos, fields: Query<(&SlowField, &GlobalTransform)>) {
+    for (field, gt) in fields.iter() {
+        gizmos.sphere(
+            Isometry3d::from_translation(gt.translation()),
+            field.radius,
+            MEDIUM_PURPLE,
+        );
+    }
+}
@@ -0,0 +1,402 @@
+//! Wizard abilities: casting costs mana, puts the ability on cooldown and then
+//! applies its [`AbilityEffect`].
+//!
+//! Casting goes through a shared pipeline: anything can request a cast with
+//! [`CastAbility`], which gets validated and turned into either an
+//! [`AbilityCast`] or a [`CastFailed`]. Effects, VFX and audio all react to
+//! those events.
+
+pub mod book;
+mod effects;
+
+use avian3d::prelude::{SpatialQuery, SpatialQueryFilter};
+use bevy::ecs::spawn::SpawnIter;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_auto_plugin::auto_plugin::*;
+
+use crate::game::build::BuildMode;
+use crate::game::camera::{MainCamera, cursor_ray};
+use crate::game::health::Dead;
+use crate::game::pause_controller::PausableSystems;
+use crate::game::physics::layers::GameLayer;
+use crate::game::screens::Screen;
+use crate::game::theme::widget;
+
+use book::{AbilityBook, AbilityEffect, AbilityId};
+
+/// How far the pointer can be from the camera and still pick a target.
+const MAX_TARGET_DISTANCE: f32 = 5000.0;
+
+#[auto_register_type]
+#[derive(Component, Debug, Copy, Clone, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct Mana {
+    pub current: f32,
+    pub max: f32,
+    pub regen_per_second: f32,
+}
+
+impl Mana {
+    pub fn new(max: f32, regen_per_second: f32) -> Self {
+        Self {
+            current: max,
+            max,
+            regen_per_second,
+        }
+    }
+}
+
+/// Something that can cast abilities from the [`AbilityBook`].
+#[auto_register_type]
+#[derive(Component, Debug, Default, Clone, Reflect)]
+#[reflect(Component)]
+#[require(Mana = Mana::new(100.0, 5.0))]
+pub struct AbilityCaster {
+    /// Remaining cooldown in seconds, indexed by [`AbilityId`].
+    cooldowns: Vec<f32>,
+}
+
+impl AbilityCaster {
+    pub fn cooldown_remaining(&self, ability: AbilityId) -> f32 {
+        self.cooldowns.get(ability.0).copied().unwrap_or_default()
+    }
+
+    fn start_cooldown(&mut self, ability: AbilityId, secs: f32) {
+        if self.cooldowns.len() <= ability.0 {
+            self.cooldowns.resize(ability.0 + 1, 0.0);
+        }
+        self.cooldowns[ability.0] = secs;
+    }
+}
+
+/// Abilities are cast from the first descendant of the caster with this,
+/// falling back to the caster itself.
+#[auto_register_type]
+#[derive(Component, Debug, Default, Copy, Clone, Reflect)]
+#[reflect(Component)]
+#[require(Transform)]
+pub struct CastOrigin;
+
+/// Request for `caster` to cast `ability`.
+#[auto_add_event]
+#[derive(Event, Debug, Copy, Clone, Reflect)]
+pub struct CastAbility {
+    pub caster: Entity,
+    pub ability: AbilityId,
+    pub target: Option<Vec3>,
+}
+
+/// A [`CastAbility`] that passed validation and is being applied.
+#[auto_add_event]
+#[derive(Event, Debug, Copy, Clone, Reflect)]
+pub struct AbilityCast {
+    pub caster: Entity,
+    pub ability: AbilityId,
+    pub effect: AbilityEffect,
+    pub origin: Vec3,
+    pub target: Option<Vec3>,
+}
+
+#[auto_add_event]
+#[derive(Event, Debug, Copy, Clone, Reflect)]
+pub struct CastFailed {
+    pub caster: Entity,
+    pub ability: AbilityId,
+    pub reason: CastError,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Reflect)]
+pub enum CastError {
+    UnknownAbility,
+    OnCooldown,
+    NotEnoughMana,
+    NoTarget,
+}
+
+#[derive(SystemSet, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum AbilitySystems {
+    /// Input and anything else writing [`CastAbility`].
+    Request,
+    /// Spends mana and starts cooldowns, writing [`AbilityCast`] or [`CastFailed`].
+    Validate,
+    /// Effects reading [`AbilityCast`].
+    Apply,
+}
+
+/// Ability picked from the HUD, waiting for a click on the ground.
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, Default, Copy, Clone, Reflect)]
+#[reflect(Resource)]
+struct ArmedAbility(Option<AbilityId>);
+
+#[auto_register_type]
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct ManaLabel;
+
+#[auto_register_type]
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct AbilityStatusLabel;
+
+#[auto_plugin(app=app)]
+pub(crate) fn plugin(app: &mut App) {
+    app.add_plugins((book::plugin, effects::plugin));
+
+    app.configure_sets(
+        Update,
+        (
+            AbilitySystems::Request,
+            AbilitySystems::Validate,
+            AbilitySystems::Apply,
+        )
+            .chain(),
+    );
+    app.add_systems(OnEnter(Screen::Gameplay), spawn_ability_hud);
+    app.add_systems(OnExit(Screen::Gameplay), disarm_ability);
+    app.add_systems(
+        Update,
+        (request_hotkey_casts, request_armed_cast)
+            .in_set(AbilitySystems::Request)
+            .in_set(PausableSystems)
+            .run_if(in_state(BuildMode(false))),
+    );
+    app.add_systems(
+        Update,
+        validate_casts
+            .in_set(AbilitySystems::Validate)
+            .in_set(PausableSystems),
+    );
+    app.add_systems(
+        Update,
+        (regen_mana, tick_cooldowns)
+            .before(AbilitySystems::Validate)
+            .in_set(PausableSystems),
+    );
+    app.add_systems(
+        Update,
+        update_ability_hud.run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// Point on the terrain under the pointer.
+#[derive(SystemParam)]
+struct CursorGround<'w, 's> {
+    windows: Query<'w, 's, &'static Window, With<PrimaryWindow>>,
+    cameras: Query<'w, 's, (&'static Camera, &'static GlobalTransform), With<MainCamera>>,
+    spatial_query: SpatialQuery<'w, 's>,
+}
+
+impl CursorGround<'_, '_> {
+    fn point(&self) -> Option<Vec3> {
+        let window = self.windows.single().ok()?;
+        let (camera, camera_gt) = self.cameras.single().ok()?;
+        let ray = cursor_ray(window, camera, camera_gt)?;
+        let hit = self.spatial_query.cast_ray(
+            ray.origin,
+            ray.direction,
+            MAX_TARGET_DISTANCE,
+            true,
+            &SpatialQueryFilter::from_mask(GameLayer::Terrain),
+        )?;
+        Some(ray.get_point(hit.distance))
+    }
+}
+
+fn disarm_ability(mut armed: ResMut<ArmedAbility>) {
+    armed.0 = None;
+}
+
+fn request_hotkey_casts(
+    keys: Res<ButtonInput<KeyCode>>,
+    book: Res<AbilityBook>,
+    casters: Query<Entity, (With<AbilityCaster>, Without<Dead>)>,
+    cursor_ground: CursorGround,
+    mut cast_writer: EventWriter<CastAbility>,
+) {
+    for (ability, def) in book.iter() {
+        if !keys.just_pressed(def.hotkey) {
+            continue;
+        }
+        let target = cursor_ground.point();
+        for caster in casters.iter() {
+            cast_writer.write(CastAbility {
+                caster,
+                ability,
+                target,
+            });
+        }
+    }
+}
+
+fn request_armed_cast(
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut armed: ResMut<ArmedAbility>,
+    casters: Query<Entity, (With<AbilityCaster>, Without<Dead>)>,
+    cursor_ground: CursorGround,
+    mut cast_writer: EventWriter<CastAbility>,
+) {
+    let Some(ability) = armed.0 else {
+        return;
+    };
+    if mouse_input.just_pressed(MouseButton::Right) || keys.just_pressed(KeyCode::Escape) {
+        armed.0 = None;
+        return;
+    }
+    if !mouse_input.just_pressed(MouseButton::Left) {
+        return;
+    }
+    let Some(target) = cursor_ground.point() else {
+        return;
+    };
+    armed.0 = None;
+    for caster in casters.iter() {
+        cast_writer.write(CastAbility {
+            caster,
+            ability,
+            target: Some(target),
+        });
+    }
+}
+
+fn validate_casts(
+    mut requests: EventReader<CastAbility>,
+    book: Res<AbilityBook>,
+    mut casters: Query<(&mut Mana, &mut AbilityCaster, &GlobalTransform), Without<Dead>>,
+    children: Query<&Children>,
+    origins: Query<&GlobalTransform, With<CastOrigin>>,
+    mut cast_writer: EventWriter<AbilityCast>,
+    mut failed_writer: EventWriter<CastFailed>,
+) {
+    for request in requests.read() {
+        let fail = |reason| CastFailed {
+            caster: request.caster,
+            ability: request.ability,
+            reason,
+        };
+        let Ok((mut mana, mut caster, caster_gt)) = casters.get_mut(request.caster) else {
+            continue;
+        };
+        let Some(def) = book.get(request.ability) else {
+            failed_writer.write(fail(CastError::UnknownAbility));
+            continue;
+        };
+        if caster.cooldown_remaining(request.ability) > 0.0 {
+            failed_writer.write(fail(CastError::OnCooldown));
+            continue;
+        }
+        if mana.current < def.mana_cost {
+            failed_writer.write(fail(CastError::NotEnoughMana));
+            continue;
+        }
+        if def.effect.needs_target() && request.target.is_none() {
+            failed_writer.write(fail(CastError::NoTarget));
+            continue;
+        }
+
+        mana.current -= def.mana_cost;
+        caster.start_cooldown(request.ability, def.cooldown_secs);
+        let origin = children
+            .iter_descendants(request.caster)
+            .find_map(|child| origins.get(child).ok())
+            .unwrap_or(caster_gt)
+            .translation();
+        cast_writer.write(AbilityCast {
+            caster: request.caster,
+            ability: request.ability,
+            effect: def.effect,
+            origin,
+            target: request.target,
+        });
+    }
+}
+
+fn regen_mana(time: Res<Time>, mut manas: Query<&mut Mana, Without<Dead>>) {
+    for mut mana in manas.iter_mut() {
+        mana.current = (mana.current + mana.regen_per_second * time.delta_secs()).min(mana.max);
+    }
+}
+
+fn tick_cooldowns(time: Res<Time>, mut casters: Query<&mut AbilityCaster>) {
+    for mut caster in casters.iter_mut() {
+        for cooldown in caster.cooldowns.iter_mut() {
+            *cooldown = (*cooldown - time.delta_secs()).max(0.0);
+        }
+    }
+}
+
+fn spawn_ability_hud(mut commands: Commands, book: Res<AbilityBook>) {
+    let buttons: Vec<_> = book
+        .iter()
+        .map(|(ability, def)| {
+            widget::button_medium(
+                def.name.clone(),
+                move |_: Trigger<Pointer<Click>>, mut armed: ResMut<ArmedAbility>| {
+                    armed.0 = Some(ability);
+                },
+            )
+        })
+        .collect();
+    commands.spawn((
+        Name::new("Ability HUD"),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(20.0),
+            width: Val::Percent(100.0),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(10.0),
+            ..default()
+        },
+        Pickable::IGNORE,
+        StateScoped(Screen::Gameplay),
+        children![
+            (widget::label(""), ManaLabel),
+            (widget::label(""), AbilityStatusLabel),
+            (
+                Name::new("Ability Buttons"),
+                Node {
+                    column_gap: Val::Px(10.0),
+                    ..default()
+                },
+                Pickable::IGNORE,
+                Children::spawn(SpawnIter(buttons.into_iter())),
+            ),
+        ],
+    ));
+}
+
+fn update_ability_hud(
+    book: Res<AbilityBook>,
+    armed: Res<ArmedAbility>,
+    casters: Query<(&Mana, &AbilityCaster)>,
+    mut mana_label: Single<&mut Text, (With<ManaLabel>, Without<AbilityStatusLabel>)>,
+    mut status_label: Single<&mut Text, (With<AbilityStatusLabel>, Without<ManaLabel>)>,
+) {
+    let Some((mana, caster)) = casters.iter().next() else {
+        mana_label.0.clear();
+        status_label.0.clear();
+        return;
+    };
+    mana_label.0 = format!("Mana: {:.0}/{:.0}", mana.current, mana.max);
+    status_label.0 = book
+        .iter()
+        .map(|(ability, def)| {
+            let key = format!("{:?}", def.hotkey);
+            let key = key.trim_start_matches("Digit");
+            let cooldown = caster.cooldown_remaining(ability);
+            if armed.0 == Some(ability) {
+                format!("[{key}] {}: click to cast", def.name)
+            } else if cooldown > 0.0 {
+                format!("[{key}] {}: {cooldown:.1}s", def.name)
+            } else {
+                format!("[{key}] {} ({:.0})", def.name, def.mana_cost)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("   ");
+}
@@ -2,6 +2,7 @@
 
 use std::collections::VecDeque;
 
+use bevy::asset::RecursiveDependencyLoadState;
 use bevy::prelude::*;
 
 pub(super) fn plugin(app: &mut App) {
@@ -13,6 +14,9 @@ pub trait LoadResource {
     /// This will load the [`Resource`] as an [`Asset`]. When all of its asset dependencies
     /// have been loaded, it will be inserted as a resource. This ensures that the resource only
     /// exists when the assets are ready.
+    ///
+    /// If a dependency fails to load, the error is logged and the resource is inserted anyway,
+    /// so consumers must be prepared to handle handles that never resolve.
     fn load_resource<T: Resource + Asset + Clone + FromWorld>(&mut self) -> &mut Self;
 }
 
@@ -62,6 +66,12 @@ fn load_resource_assets(world: &mut World) {
                 if assets.is_loaded_with_dependencies(&handle) {
                     insert_fn(world, &handle);
                     resource_handles.finished.push(handle);
+                } else if let RecursiveDependencyLoadState::Failed(err) =
+                    assets.recursive_dependency_load_state(&handle)
+                {
+                    error!("Failed to load dependencies for {handle:?}: {err}");
+                    insert_fn(world, &handle);
+                    resource_handles.finished.push(handle);
                 } else {
                     resource_handles.waiting.push_back((handle, insert_fn));
                 }
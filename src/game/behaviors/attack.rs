@@ -0,0 +1,57 @@
+//! Melee attacks. Anything with an [`Attack`] hits its [`TargetEnt`] every
+//! interval while in [`BehaviorState::Attack`].
+
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+
+use super::state_machine::{BehaviorState, BehaviorSystems};
+use super::target_ent::TargetEnt;
+use crate::game::health::{AdjustHp, Dead, Health};
+
+#[auto_register_type]
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct Attack {
+    pub damage: f32,
+    pub interval: Timer,
+}
+
+impl Attack {
+    pub fn new(damage: f32, interval_secs: f32) -> Self {
+        Self {
+            damage,
+            interval: Timer::from_seconds(interval_secs, TimerMode::Repeating),
+        }
+    }
+}
+
+#[auto_plugin(app=app)]
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(Update, attack.in_set(BehaviorSystems::Attack));
+}
+
+fn attack(
+    time: Res<Time>,
+    mut attackers: Query<(Entity, &BehaviorState, &TargetEnt, &mut Attack), Without<Dead>>,
+    parents: Query<&ChildOf>,
+    damageable: Query<(), (With<Health>, Without<Dead>)>,
+    mut adjust_hp_events: EventWriter<AdjustHp>,
+) {
+    for (entity, state, target, mut attack) in attackers.iter_mut() {
+        if *state != BehaviorState::Attack {
+            attack.interval.reset();
+            continue;
+        }
+        if !attack.interval.tick(time.delta()).just_finished() {
+            continue;
+        }
+        // Hitting whatever's on top of a tower, like the wizard, hits the tower
+        let Some(victim) = std::iter::once(target.target_ent)
+            .chain(parents.iter_ancestors(target.target_ent))
+            .find(|&victim| damageable.contains(victim))
+        else {
+            continue;
+        };
+        adjust_hp_events.write(AdjustHp::new(victim, -attack.damage).with_source(entity));
+    }
+}
@@ -0,0 +1,137 @@
+//! Keeps moving agents from stacking on top of each other by steering them
+//! apart, with a spatial hash so each agent only looks at its neighbours.
+
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+use smart_default::SmartDefault;
+
+use super::target_ent::target_ent_sys;
+use crate::game::pause_controller::PausableSystems;
+
+/// Something that keeps its distance from other agents.
+#[auto_register_type]
+#[derive(Component, Debug, Copy, Clone, Reflect, SmartDefault)]
+#[reflect(Component)]
+#[require(Separation)]
+pub struct AvoidanceAgent {
+    #[default(6.0)]
+    pub radius: f32,
+}
+
+/// Push away from neighbouring agents, stronger the more crowded it is and
+/// already scaled by [`AvoidanceConfig::weight`]. Read by `target_ent_sys`.
+#[auto_register_type]
+#[derive(Component, Debug, Default, Copy, Clone, Reflect)]
+#[reflect(Component)]
+pub struct Separation(pub Vec3);
+
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, Reflect, SmartDefault)]
+#[reflect(Resource)]
+pub struct AvoidanceConfig {
+    /// Should be at least twice the largest agent radius.
+    #[default(20.0)]
+    pub cell_size: f32,
+    /// Agents start pushing apart this far beyond touching.
+    #[default(4.0)]
+    pub padding: f32,
+    /// How much [`Separation`] counts compared to heading for the target.
+    #[default(1.5)]
+    pub weight: f32,
+}
+
+/// Agents bucketed by cell on the XZ plane, rebuilt every frame.
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, Default, Reflect)]
+#[reflect(Resource)]
+pub struct SpatialHash {
+    cell_size: f32,
+    #[reflect(ignore)]
+    cells: HashMap<IVec2, Vec<(Entity, Vec3, f32)>>,
+}
+
+impl SpatialHash {
+    fn cell(&self, position: Vec3) -> IVec2 {
+        (position.xz() / self.cell_size).floor().as_ivec2()
+    }
+
+    fn rebuild(&mut self, cell_size: f32, agents: impl Iterator<Item = (Entity, Vec3, f32)>) {
+        self.cell_size = cell_size;
+        self.cells.values_mut().for_each(Vec::clear);
+        for agent in agents {
+            let cell = self.cell(agent.1);
+            self.cells.entry(cell).or_default().push(agent);
+        }
+    }
+
+    /// Agents in the cells around `position`, which includes everything within
+    /// `cell_size` of it.
+    pub fn neighbours(&self, position: Vec3) -> impl Iterator<Item = (Entity, Vec3, f32)> + '_ {
+        let center = self.cell(position);
+        (-1..=1)
+            .flat_map(move |y| (-1..=1).map(move |x| center + IVec2::new(x, y)))
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .copied()
+    }
+}
+
+#[auto_plugin(app=app)]
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (rebuild_spatial_hash, compute_separation)
+            .chain()
+            .before(target_ent_sys)
+            .in_set(PausableSystems),
+    );
+}
+
+fn rebuild_spatial_hash(
+    cfg: Res<AvoidanceConfig>,
+    mut hash: ResMut<SpatialHash>,
+    agents: Query<(Entity, &GlobalTransform, &AvoidanceAgent)>,
+) {
+    hash.rebuild(
+        cfg.cell_size,
+        agents
+            .iter()
+            .map(|(entity, gt, agent)| (entity, gt.translation(), agent.radius)),
+    );
+}
+
+fn compute_separation(
+    cfg: Res<AvoidanceConfig>,
+    hash: Res<SpatialHash>,
+    mut agents: Query<(Entity, &GlobalTransform, &AvoidanceAgent, &mut Separation)>,
+) {
+    for (entity, gt, agent, mut separation) in agents.iter_mut() {
+        let position = gt.translation();
+        let push = hash
+            .neighbours(position)
+            .filter(|&(other, ..)| other != entity)
+            .fold(Vec3::ZERO, |push, (other, other_position, other_radius)| {
+                let reach = agent.radius + other_radius + cfg.padding;
+                let offset = (position - other_position).with_y(0.0);
+                let dist = offset.length();
+                if dist >= reach {
+                    return push;
+                }
+                // Agents right on top of each other still need to split up, so
+                // send them opposite ways.
+                let away = offset.try_normalize().unwrap_or_else(|| {
+                    let angle = if entity < other {
+                        0.0
+                    } else {
+                        std::f32::consts::PI
+                    };
+                    Vec3::new(angle.cos(), 0.0, angle.sin())
+                });
+                push + away * (1.0 - dist / reach)
+            });
+        separation.0 = push.clamp_length_max(1.0) * cfg.weight;
+    }
+}
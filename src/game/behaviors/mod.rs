@@ -1,4 +1,8 @@
+pub mod attack;
+pub mod avoidance;
+pub mod state_machine;
 pub mod target_ent;
+pub mod targeting;
 
 use bevy::prelude::*;
 use bevy_auto_plugin::auto_plugin::*;
@@ -8,7 +12,25 @@ use bevy_auto_plugin::auto_plugin::*;
 #[reflect(Component)]
 pub struct MovementSpeed(pub f32);
 
+/// Scales [`MovementSpeed`], e.g. `0.5` while slowed.
+#[auto_register_type]
+#[derive(Component, Debug, Copy, Clone, Reflect)]
+#[reflect(Component)]
+pub struct SpeedMultiplier(pub f32);
+
+impl MovementSpeed {
+    pub fn effective(&self, multiplier: Option<&SpeedMultiplier>) -> f32 {
+        self.0 * multiplier.map_or(1.0, |multiplier| multiplier.0)
+    }
+}
+
 #[auto_plugin(app=app)]
 pub(crate) fn plugin(app: &mut App) {
-    app.add_plugins(target_ent::plugin);
+    app.add_plugins((
+        attack::plugin,
+        avoidance::plugin,
+        state_machine::plugin,
+        target_ent::plugin,
+        targeting::plugin,
+    ));
 }
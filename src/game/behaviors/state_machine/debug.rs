@@ -0,0 +1,85 @@
+use bevy::input::common_conditions::input_just_pressed;
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+
+use super::BehaviorState;
+use crate::game::camera::MainCamera;
+
+/// How far above an entity's origin its label floats.
+const LABEL_HEIGHT: f32 = 30.0;
+
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, Default, Copy, Clone, Reflect)]
+#[reflect(Resource)]
+struct ShowBehaviorStates(bool);
+
+/// Label showing the [`BehaviorState`] of the entity it follows.
+#[auto_register_type]
+#[derive(Component, Debug, Copy, Clone, Reflect)]
+#[reflect(Component)]
+struct BehaviorStateLabel(Entity);
+
+#[auto_plugin(app=app)]
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        toggle_behavior_states.run_if(input_just_pressed(KeyCode::F2)),
+    );
+    app.add_systems(Update, sync_behavior_state_labels);
+}
+
+fn toggle_behavior_states(mut show: ResMut<ShowBehaviorStates>) {
+    show.0 = !show.0;
+}
+
+fn sync_behavior_state_labels(
+    mut commands: Commands,
+    show: Res<ShowBehaviorStates>,
+    camera: Option<Single<(&Camera, &GlobalTransform), With<MainCamera>>>,
+    states: Query<(Entity, &BehaviorState, &GlobalTransform)>,
+    mut labels: Query<(Entity, &BehaviorStateLabel, &mut Text, &mut Node)>,
+) {
+    let Some(camera) = camera.filter(|_| show.0) else {
+        for (label, ..) in labels.iter() {
+            commands.entity(label).despawn();
+        }
+        return;
+    };
+    let (camera, camera_gt) = *camera;
+
+    for (label, &BehaviorStateLabel(entity), mut text, mut node) in labels.iter_mut() {
+        let Ok((_, state, gt)) = states.get(entity) else {
+            commands.entity(label).despawn();
+            continue;
+        };
+        let Ok(position) =
+            camera.world_to_viewport(camera_gt, gt.translation() + Vec3::Y * LABEL_HEIGHT)
+        else {
+            node.display = Display::None;
+            continue;
+        };
+        text.0 = format!("{state:?}");
+        node.display = Display::Flex;
+        node.left = Val::Px(position.x);
+        node.top = Val::Px(position.y);
+    }
+
+    for (entity, ..) in states.iter() {
+        if labels.iter().any(|(_, label, ..)| label.0 == entity) {
+            continue;
+        }
+        commands.spawn((
+            Name::new("Behavior State Label"),
+            BehaviorStateLabel(entity),
+            Text::default(),
+            TextFont::from_font_size(14.0),
+            Node {
+                position_type: PositionType::Absolute,
+                display: Display::None,
+                ..default()
+            },
+            Pickable::IGNORE,
+        ));
+    }
+}
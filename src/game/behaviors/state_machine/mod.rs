@@ -0,0 +1,253 @@
+//! Behaviour state machine. Each frame the [`BehaviorState`] of every entity is
+//! re-evaluated from its circumstances, triggering [`BehaviorExit`] and
+//! [`BehaviorEnter`] on the entity when it changes. Systems that drive a state
+//! go in that state's [`BehaviorSystems`] set, which runs after transitions.
+
+#[cfg(feature = "dev")]
+mod debug;
+
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+use smart_default::SmartDefault;
+
+use super::MovementSpeed;
+use super::SpeedMultiplier;
+use super::target_ent::TargetEnt;
+use crate::game::despawn::DespawnAfter;
+use crate::game::health::{Dead, Health, MaxHealth};
+use crate::game::pause_controller::PausableSystems;
+use crate::game::physics::character::{CharacterControllerSystems, DesiredVelocity};
+
+#[auto_register_type]
+#[derive(Component, Debug, Default, Copy, Clone, PartialEq, Eq, Hash, Reflect)]
+#[reflect(Component)]
+#[require(BehaviorConfig)]
+pub enum BehaviorState {
+    #[default]
+    Idle,
+    Chase,
+    Attack,
+    Flee,
+    Stunned,
+    Dead,
+}
+
+#[auto_register_type]
+#[derive(Component, Debug, Copy, Clone, Reflect, SmartDefault)]
+#[reflect(Component)]
+pub struct BehaviorConfig {
+    /// Flee once health drops below this fraction of [`MaxHealth`].
+    #[default(0.25)]
+    pub flee_health_fraction: f32,
+    /// How long the body stays around after entering [`BehaviorState::Dead`].
+    #[default(3.0)]
+    pub corpse_secs: f32,
+}
+
+/// Puts an entity in [`BehaviorState::Stunned`] until the timer finishes.
+#[auto_register_type]
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct Stun(pub Timer);
+
+impl Stun {
+    pub fn secs(secs: f32) -> Self {
+        Self(Timer::from_seconds(secs, TimerMode::Once))
+    }
+}
+
+/// Triggered on an entity as it enters a state.
+#[derive(Event, Debug, Copy, Clone)]
+pub struct BehaviorEnter(pub BehaviorState);
+
+/// Triggered on an entity as it leaves a state, before the [`BehaviorEnter`]
+/// of the next one.
+#[derive(Event, Debug, Copy, Clone)]
+pub struct BehaviorExit(pub BehaviorState);
+
+#[derive(SystemSet, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum BehaviorSystems {
+    Transitions,
+    Idle,
+    Chase,
+    Attack,
+    Flee,
+    Stunned,
+    Dead,
+}
+
+impl From<BehaviorState> for BehaviorSystems {
+    fn from(state: BehaviorState) -> Self {
+        match state {
+            BehaviorState::Idle => Self::Idle,
+            BehaviorState::Chase => Self::Chase,
+            BehaviorState::Attack => Self::Attack,
+            BehaviorState::Flee => Self::Flee,
+            BehaviorState::Stunned => Self::Stunned,
+            BehaviorState::Dead => Self::Dead,
+        }
+    }
+}
+
+#[auto_plugin(app=app)]
+pub(crate) fn plugin(app: &mut App) {
+    #[cfg(feature = "dev")]
+    app.add_plugins(debug::plugin);
+
+    app.configure_sets(
+        Update,
+        (
+            BehaviorSystems::Transitions,
+            (
+                BehaviorSystems::Idle,
+                BehaviorSystems::Chase,
+                BehaviorSystems::Attack,
+                BehaviorSystems::Flee,
+                BehaviorSystems::Stunned,
+                BehaviorSystems::Dead,
+            )
+                .before(CharacterControllerSystems),
+        )
+            .chain()
+            .in_set(PausableSystems),
+    );
+    app.add_observer(stop_on_enter);
+    app.add_observer(die_on_enter);
+    app.add_systems(
+        Update,
+        (tick_stuns, update_behavior_states)
+            .chain()
+            .in_set(BehaviorSystems::Transitions),
+    );
+    app.add_systems(Update, flee.in_set(BehaviorSystems::Flee));
+}
+
+/// What an entity is doing right now, in order of priority.
+fn next_state(
+    dead: bool,
+    stunned: bool,
+    health_fraction: Option<f32>,
+    target_distance: Option<(f32, f32)>,
+    cfg: &BehaviorConfig,
+) -> BehaviorState {
+    if dead {
+        return BehaviorState::Dead;
+    }
+    if stunned {
+        return BehaviorState::Stunned;
+    }
+    let Some((distance, within_distance)) = target_distance else {
+        return BehaviorState::Idle;
+    };
+    if health_fraction.is_some_and(|fraction| fraction < cfg.flee_health_fraction) {
+        return BehaviorState::Flee;
+    }
+    if distance > within_distance {
+        BehaviorState::Chase
+    } else {
+        BehaviorState::Attack
+    }
+}
+
+fn tick_stuns(mut commands: Commands, time: Res<Time>, mut stuns: Query<(Entity, &mut Stun)>) {
+    for (entity, mut stun) in stuns.iter_mut() {
+        if stun.0.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<Stun>();
+        }
+    }
+}
+
+pub(super) fn update_behavior_states(
+    mut commands: Commands,
+    mut entities: Query<(
+        Entity,
+        &mut BehaviorState,
+        &BehaviorConfig,
+        &GlobalTransform,
+        Option<&TargetEnt>,
+        Option<(&Health, &MaxHealth)>,
+        Has<Dead>,
+        Has<Stun>,
+    )>,
+    targets: Query<&GlobalTransform>,
+) {
+    for (entity, mut state, cfg, gt, target, health, dead, stunned) in entities.iter_mut() {
+        // Dead is final
+        if *state == BehaviorState::Dead {
+            continue;
+        }
+        let target_distance = target.and_then(|target| {
+            let target_gt = targets.get(target.target_ent).ok()?;
+            Some((
+                gt.translation().xz().distance(target_gt.translation().xz()),
+                target.within_distance,
+            ))
+        });
+        let health_fraction = health.map(|(health, max_health)| health.0 / max_health.0);
+        let next = next_state(dead, stunned, health_fraction, target_distance, cfg);
+        if next == *state {
+            continue;
+        }
+        commands.trigger_targets(BehaviorExit(*state), entity);
+        commands.trigger_targets(BehaviorEnter(next), entity);
+        *state = next;
+    }
+}
+
+/// States that don't move stop in their tracks.
+fn stop_on_enter(trigger: Trigger<BehaviorEnter>, mut desired: Query<&mut DesiredVelocity>) {
+    let BehaviorEnter(BehaviorState::Idle | BehaviorState::Stunned | BehaviorState::Dead) =
+        *trigger.event()
+    else {
+        return;
+    };
+    if let Ok(mut desired) = desired.get_mut(trigger.target()) {
+        desired.0 = Vec3::ZERO;
+    }
+}
+
+fn die_on_enter(
+    trigger: Trigger<BehaviorEnter>,
+    mut commands: Commands,
+    configs: Query<&BehaviorConfig>,
+) {
+    let BehaviorEnter(BehaviorState::Dead) = *trigger.event() else {
+        return;
+    };
+    let Ok(cfg) = configs.get(trigger.target()) else {
+        return;
+    };
+    commands
+        .entity(trigger.target())
+        .remove::<TargetEnt>()
+        .insert(DespawnAfter::secs(cfg.corpse_secs));
+}
+
+/// Runs directly away from the target.
+fn flee(
+    mut fleeing: Query<(
+        &BehaviorState,
+        &TargetEnt,
+        &mut Transform,
+        &mut DesiredVelocity,
+        Option<&MovementSpeed>,
+        Option<&SpeedMultiplier>,
+    )>,
+    targets: Query<&GlobalTransform>,
+) {
+    for (state, target, mut transform, mut desired, speed, multiplier) in fleeing.iter_mut() {
+        if *state != BehaviorState::Flee {
+            continue;
+        }
+        let Ok(target_gt) = targets.get(target.target_ent) else {
+            continue;
+        };
+        let away = (transform.translation - target_gt.translation())
+            .with_y(0.0)
+            .normalize_or_zero();
+        let speed = speed.map_or(0.0, |speed| speed.effective(multiplier));
+        desired.0 = away * speed;
+        let facing = transform.translation + away;
+        transform.look_at(facing, Vec3::Y);
+    }
+}
@@ -1,5 +1,10 @@
-use super::MovementSpeed;
-use crate::game::pause_controller::PausableSystems;
+use super::avoidance::Separation;
+use super::state_machine::{BehaviorState, BehaviorSystems, Stun};
+use super::{MovementSpeed, SpeedMultiplier};
+use crate::game::health::Dead;
+use crate::game::navigation::NavConfig;
+use crate::game::navigation::path::PathFollower;
+use crate::game::physics::character::DesiredVelocity;
 use bevy::prelude::*;
 use bevy_auto_plugin::auto_plugin::*;
 
@@ -11,42 +16,107 @@ pub struct TargetEnt {
     pub within_distance: f32,
 }
 
-fn target_ent_sys(
+pub(super) fn target_ent_sys(
     mut commands: Commands,
     time: Res<Time>,
-    target_q: Query<(Entity, &TargetEnt, Option<&MovementSpeed>)>,
+    nav_cfg: Res<NavConfig>,
+    mut target_q: Query<
+        (
+            Entity,
+            &TargetEnt,
+            Option<&MovementSpeed>,
+            Option<&SpeedMultiplier>,
+            Option<&mut PathFollower>,
+            Option<&Separation>,
+            Option<&mut DesiredVelocity>,
+            Option<&BehaviorState>,
+        ),
+        Without<Stun>,
+    >,
     mut transform_q: Query<&mut Transform>,
+    dead_q: Query<(), With<Dead>>,
 ) {
-    for (self_ent, &target, movement_speed) in target_q.iter() {
+    for (self_ent, &target, movement_speed, speed_multiplier, path, separation, desired, state) in
+        target_q.iter_mut()
+    {
+        // Entities with a state machine only move here while chasing or attacking
+        if state.is_some_and(|state| !matches!(state, BehaviorState::Chase | BehaviorState::Attack))
+        {
+            continue;
+        }
         let target_ent = target.target_ent;
-        // If target ent no longer exists, remove component
-        let Ok(target_trans) = transform_q.get(target_ent).cloned() else {
+        // If target ent no longer exists or is dead, remove component
+        let target_trans = transform_q
+            .get(target_ent)
+            .ok()
+            .filter(|_| !dead_q.contains(target_ent))
+            .cloned();
+        let Some(target_trans) = target_trans else {
             commands.entity(self_ent).remove::<TargetEnt>();
-            return;
+            continue;
         };
 
-        // Face target
         let mut self_trans = transform_q.get_mut(self_ent).unwrap();
-        self_trans.look_at(target_trans.translation, Vec3::Y);
+
+        // Head for the next waypoint if there's a path, otherwise straight at
+        // the target.
+        let waypoint = path.and_then(|mut path| {
+            path.advance(self_trans.translation, nav_cfg.waypoint_radius);
+            path.next_waypoint()
+        });
+        let heading = waypoint.map_or(target_trans.translation, |waypoint| {
+            waypoint.with_y(self_trans.translation.y)
+        });
+
+        // Face where we're heading, staying upright
+        self_trans.look_at(heading.with_y(self_trans.translation.y), Vec3::Y);
+        let Some(mut desired) = desired else {
+            continue;
+        };
 
         // If target is outside range (`within_distance`), move towards it,
-        // otherwise attack.
-        let dist = self_trans.translation.distance(target_trans.translation);
+        // otherwise circle it while `Attack` does the hitting.
+        let speed = movement_speed.map_or(0.0, |speed| speed.effective(speed_multiplier));
+        let separation = separation.map_or(Vec3::ZERO, |separation| separation.0);
+        // Ground units can't reach up, so only horizontal distance counts
+        let dist = self_trans
+            .translation
+            .xz()
+            .distance(target_trans.translation.xz());
         if dist > target.within_distance {
-            if let Some(move_speed) = movement_speed {
-                let move_speed = move_speed.0 * time.delta_secs();
-                let move_dist = move_speed.min(dist - target.within_distance);
-                self_trans.translation = self_trans
-                    .translation
-                    .move_towards(target_trans.translation, move_dist);
-            }
+            // Don't overshoot into the attack radius
+            let max_speed = (dist - target.within_distance) / time.delta_secs().max(f32::EPSILON);
+            let direction = ((heading - self_trans.translation).normalize_or_zero() + separation)
+                .normalize_or_zero();
+            desired.0 = direction * speed.min(max_speed);
         } else {
-            // TODO trigger attack
+            // Spread out around the target by sliding along the attack radius,
+            // away from neighbours
+            let radial = (self_trans.translation - target_trans.translation)
+                .with_y(0.0)
+                .normalize_or_zero();
+            let tangential = separation - radial * separation.dot(radial);
+            desired.0 = tangential.clamp_length_max(1.0) * speed;
         }
     }
 }
 
 #[auto_plugin(app=app)]
 pub(crate) fn plugin(app: &mut App) {
-    app.add_systems(Update, target_ent_sys.in_set(PausableSystems));
+    app.add_observer(stop_on_target_removed);
+    app.add_systems(
+        Update,
+        target_ent_sys
+            .in_set(BehaviorSystems::Chase)
+            .in_set(BehaviorSystems::Attack),
+    );
+}
+
+fn stop_on_target_removed(
+    trigger: Trigger<OnRemove, TargetEnt>,
+    mut desired: Query<&mut DesiredVelocity>,
+) {
+    if let Ok(mut desired) = desired.get_mut(trigger.target()) {
+        desired.0 = Vec3::ZERO;
+    }
 }
@@ -0,0 +1,175 @@
+//! Picks what each [`Targeting`] entity chases, re-evaluated every frame. In
+//! order of priority: whoever taunted it, whoever hurt it last, the wizard if
+//! it's close, and otherwise the nearest tower.
+
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+use smart_default::SmartDefault;
+
+use super::state_machine::BehaviorSystems;
+use super::target_ent::TargetEnt;
+use crate::game::health::{AdjustHp, Dead};
+use crate::game::prefabs::tower::Tower;
+use crate::game::prefabs::wizard::Wizard;
+
+#[auto_register_type]
+#[derive(Component, Debug, Copy, Clone, Reflect, SmartDefault)]
+#[reflect(Component)]
+pub struct Targeting {
+    /// Used for the [`TargetEnt`] of whatever gets picked.
+    #[default(20.0)]
+    pub within_distance: f32,
+    /// The wizard gets picked over towers when it's at most this far away.
+    #[default(150.0)]
+    pub wizard_range: f32,
+    /// How long an attacker stays the target after its last hit.
+    #[default(5.0)]
+    pub aggro_secs: f32,
+}
+
+/// Forces the target to `source` until the timer finishes.
+#[auto_register_type]
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct Taunt {
+    pub source: Entity,
+    pub timer: Timer,
+}
+
+impl Taunt {
+    pub fn new(source: Entity, secs: f32) -> Self {
+        Self {
+            source,
+            timer: Timer::from_seconds(secs, TimerMode::Once),
+        }
+    }
+}
+
+/// Last thing that damaged this entity, forgotten after [`Targeting::aggro_secs`].
+#[auto_register_type]
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct Aggro {
+    pub source: Entity,
+    pub timer: Timer,
+}
+
+#[auto_plugin(app=app)]
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (record_aggro, tick_aggro_and_taunts, select_targets)
+            .chain()
+            .in_set(BehaviorSystems::Transitions)
+            .before(super::state_machine::update_behavior_states),
+    );
+}
+
+fn record_aggro(
+    mut commands: Commands,
+    mut adjust_hp_events: EventReader<AdjustHp>,
+    targeting: Query<&Targeting, Without<Dead>>,
+) {
+    for event in adjust_hp_events.read() {
+        let Some(source) = event.source else {
+            continue;
+        };
+        if event.amount >= 0.0 || source == event.target {
+            continue;
+        }
+        let Ok(cfg) = targeting.get(event.target) else {
+            continue;
+        };
+        commands.entity(event.target).insert(Aggro {
+            source,
+            timer: Timer::from_seconds(cfg.aggro_secs, TimerMode::Once),
+        });
+    }
+}
+
+fn tick_aggro_and_taunts(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut aggro: Query<(Entity, &mut Aggro)>,
+    mut taunts: Query<(Entity, &mut Taunt)>,
+) {
+    for (entity, mut aggro) in aggro.iter_mut() {
+        if aggro.timer.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<Aggro>();
+        }
+    }
+    for (entity, mut taunt) in taunts.iter_mut() {
+        if taunt.timer.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<Taunt>();
+        }
+    }
+}
+
+fn select_targets(
+    mut commands: Commands,
+    entities: Query<
+        (
+            Entity,
+            &Targeting,
+            &GlobalTransform,
+            Option<&TargetEnt>,
+            Option<&Taunt>,
+            Option<&Aggro>,
+        ),
+        Without<Dead>,
+    >,
+    alive: Query<&GlobalTransform, Without<Dead>>,
+    towers: Query<Entity, With<Tower>>,
+    wizards: Query<Entity, With<Wizard>>,
+    parents: Query<&ChildOf>,
+) {
+    for (entity, cfg, gt, current, taunt, aggro) in entities.iter() {
+        let position = gt.translation();
+        let distance = |target: Entity| {
+            alive
+                .get(target)
+                .ok()
+                .map(|target_gt| target_gt.translation().xz().distance(position.xz()))
+        };
+        // Sparks zapping us are parented to us, so chasing them goes nowhere
+        let chaseable = |target: Entity| {
+            target != entity
+                && !parents
+                    .iter_ancestors(target)
+                    .any(|ancestor| ancestor == entity)
+                && alive.contains(target)
+        };
+        let nearest = |candidates: &mut dyn Iterator<Item = Entity>, max_distance: f32| {
+            candidates
+                .filter_map(|candidate| Some((candidate, distance(candidate)?)))
+                .filter(|&(_, dist)| dist <= max_distance)
+                .min_by(|(_, a), (_, b)| a.total_cmp(b))
+                .map(|(candidate, _)| candidate)
+        };
+
+        let target = taunt
+            .map(|taunt| taunt.source)
+            .filter(|&source| chaseable(source))
+            .or_else(|| {
+                aggro
+                    .map(|aggro| aggro.source)
+                    .filter(|&source| chaseable(source))
+            })
+            .or_else(|| nearest(&mut wizards.iter(), cfg.wizard_range))
+            .or_else(|| nearest(&mut towers.iter(), f32::INFINITY));
+
+        match (target, current) {
+            (Some(target), Some(current)) if current.target_ent == target => {}
+            (Some(target), _) => {
+                commands.entity(entity).insert(TargetEnt {
+                    target_ent: target,
+                    within_distance: cfg.within_distance,
+                });
+            }
+            (None, Some(_)) => {
+                commands.entity(entity).remove::<TargetEnt>();
+            }
+            (None, None) => {}
+        }
+    }
+}
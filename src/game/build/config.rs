@@ -0,0 +1,21 @@
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+use smart_default::SmartDefault;
+
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Reflect, SmartDefault)]
+#[reflect(Resource)]
+pub struct BuildConfig {
+    /// Towers snap to a grid of this size on the ground plane.
+    #[default(25.0)]
+    pub grid_size: f32,
+    /// Steepest ground, as the y of its normal, a tower can be placed on.
+    #[default(0.9)]
+    pub min_ground_normal_y: f32,
+    #[default(5000.0)]
+    pub max_pointer_distance: f32,
+}
+
+#[auto_plugin(app=app)]
+pub(super) fn plugin(app: &mut App) {}
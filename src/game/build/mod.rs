@@ -0,0 +1,315 @@
+//! Build mode: placing new towers and upgrading existing ones.
+
+mod config;
+
+use avian3d::prelude::{SpatialQuery, SpatialQueryFilter};
+use bevy::color::palettes::css::{LIME, RED};
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_auto_plugin::auto_plugin::*;
+
+use crate::game::camera::{MainCamera, cursor_ray};
+use crate::game::currency::Currency;
+use crate::game::menus::Menu;
+use crate::game::pause_controller::PausableSystems;
+use crate::game::physics::layers::GameLayer;
+use crate::game::prefabs::tower::{Tower, TowerConfig, TowerLevel};
+use crate::game::screens::Screen;
+use crate::game::settings::{KeyBindings, binding_just_pressed, key_name};
+use crate::game::theme::widget;
+
+use config::*;
+
+/// Whether the player is placing or upgrading towers.
+#[auto_register_state_type]
+#[auto_init_state]
+#[derive(States, Copy, Clone, Eq, PartialEq, Hash, Debug, Default, Reflect)]
+#[states(scoped_entities)]
+pub struct BuildMode(pub bool);
+
+/// Translucent preview of the tower about to be placed.
+#[auto_register_type]
+#[auto_name]
+#[derive(Component, Debug, Default, Copy, Clone, Reflect)]
+#[reflect(Component)]
+#[require(Transform)]
+struct TowerGhost;
+
+/// What clicking would do, based on what's under the pointer.
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, Default, Copy, Clone, PartialEq, Reflect)]
+#[reflect(Resource)]
+enum BuildAction {
+    #[default]
+    None,
+    Place {
+        position: Vec3,
+        valid: bool,
+    },
+    Upgrade {
+        tower: Entity,
+        /// `None` once the tower is fully upgraded.
+        cost: Option<u32>,
+    },
+}
+
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, Clone, Reflect)]
+#[reflect(Resource)]
+struct TowerGhostMaterials {
+    valid: Handle<StandardMaterial>,
+    invalid: Handle<StandardMaterial>,
+}
+
+impl FromWorld for TowerGhostMaterials {
+    fn from_world(world: &mut World) -> Self {
+        let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
+        let mut ghost = |color: Srgba| {
+            materials.add(StandardMaterial {
+                base_color: color.with_alpha(0.4).into(),
+                alpha_mode: AlphaMode::Blend,
+                unlit: true,
+                ..Default::default()
+            })
+        };
+        Self {
+            valid: ghost(LIME),
+            invalid: ghost(RED),
+        }
+    }
+}
+
+#[auto_register_type]
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct CurrencyLabel;
+
+#[auto_register_type]
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct BuildHintLabel;
+
+#[auto_plugin(app=app)]
+pub(crate) fn plugin(app: &mut App) {
+    app.add_plugins(config::plugin);
+
+    app.add_systems(OnEnter(Screen::Gameplay), spawn_build_hud);
+    app.add_systems(OnExit(Screen::Gameplay), exit_build_mode);
+    app.add_systems(OnEnter(BuildMode(true)), spawn_tower_ghost);
+    app.add_systems(OnExit(BuildMode(true)), reset_build_action);
+
+    app.add_systems(
+        Update,
+        toggle_build_mode.run_if(
+            in_state(Screen::Gameplay)
+                .and(in_state(Menu::None))
+                .and(binding_just_pressed(|keys| keys.build_mode)),
+        ),
+    );
+    app.add_systems(
+        Update,
+        (update_build_action, update_tower_ghost, apply_build_action)
+            .chain()
+            .in_set(PausableSystems)
+            .run_if(in_state(BuildMode(true))),
+    );
+    app.add_systems(Update, update_build_hud.run_if(in_state(Screen::Gameplay)));
+}
+
+fn toggle_build_mode(build_mode: Res<State<BuildMode>>, mut next: ResMut<NextState<BuildMode>>) {
+    next.set(BuildMode(!build_mode.get().0));
+}
+
+fn exit_build_mode(mut next: ResMut<NextState<BuildMode>>) {
+    next.set(BuildMode(false));
+}
+
+fn reset_build_action(mut action: ResMut<BuildAction>) {
+    *action = BuildAction::None;
+}
+
+fn spawn_tower_ghost(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    tower_cfg: Res<TowerConfig>,
+    materials: Res<TowerGhostMaterials>,
+) {
+    let height = tower_cfg.stats(TowerLevel::default()).height;
+    let mesh = Cylinder::new(tower_cfg.radius, height)
+        .mesh()
+        .build()
+        .translated_by(Vec3::Y * height / 2.0);
+    commands.spawn((
+        TowerGhost,
+        StateScoped(BuildMode(true)),
+        Mesh3d(meshes.add(mesh)),
+        MeshMaterial3d(materials.invalid.clone()),
+        Visibility::Hidden,
+        Pickable::IGNORE,
+    ));
+}
+
+fn update_build_action(
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &GlobalTransform), With<MainCamera>>,
+    spatial_query: SpatialQuery,
+    parents: Query<&ChildOf>,
+    towers: Query<&TowerLevel, With<Tower>>,
+    tower_cfg: Res<TowerConfig>,
+    cfg: Res<BuildConfig>,
+    currency: Res<Currency>,
+    mut action: ResMut<BuildAction>,
+) {
+    let (camera, camera_gt) = *camera;
+    let Some(ray) = cursor_ray(&window, camera, camera_gt) else {
+        *action = BuildAction::None;
+        return;
+    };
+
+    let Some(hit) = spatial_query.cast_ray(
+        ray.origin,
+        ray.direction,
+        cfg.max_pointer_distance,
+        true,
+        &SpatialQueryFilter::from_mask([GameLayer::Terrain, GameLayer::Tower]),
+    ) else {
+        *action = BuildAction::None;
+        return;
+    };
+
+    // Pointing at a tower (or something on top of it) upgrades it
+    let tower = std::iter::once(hit.entity)
+        .chain(parents.iter_ancestors(hit.entity))
+        .find_map(|entity| towers.get(entity).ok().map(|level| (entity, level)));
+    if let Some((tower, level)) = tower {
+        *action = BuildAction::Upgrade {
+            tower,
+            cost: tower_cfg.upgrade_cost(*level),
+        };
+        return;
+    }
+
+    let point = ray.get_point(hit.distance);
+    let snapped = (point / cfg.grid_size).round() * cfg.grid_size;
+    let position = Vec3::new(snapped.x, point.y, snapped.z);
+    let height = tower_cfg.stats(TowerLevel::default()).height;
+    let overlaps = spatial_query.shape_intersections(
+        &tower_cfg.collider(height),
+        position,
+        Quat::IDENTITY,
+        &SpatialQueryFilter::from_mask([GameLayer::Tower, GameLayer::Enemy]),
+    );
+    let valid = overlaps.is_empty()
+        && hit.normal.y >= cfg.min_ground_normal_y
+        && currency.0 >= tower_cfg.build_cost;
+    *action = BuildAction::Place { position, valid };
+}
+
+fn update_tower_ghost(
+    action: Res<BuildAction>,
+    materials: Res<TowerGhostMaterials>,
+    mut ghost: Single<
+        (
+            &mut Transform,
+            &mut Visibility,
+            &mut MeshMaterial3d<StandardMaterial>,
+        ),
+        With<TowerGhost>,
+    >,
+) {
+    let (transform, visibility, material) = &mut *ghost;
+    let BuildAction::Place { position, valid } = *action else {
+        **visibility = Visibility::Hidden;
+        return;
+    };
+    transform.translation = position;
+    **visibility = Visibility::Inherited;
+    material.0 = if valid {
+        materials.valid.clone()
+    } else {
+        materials.invalid.clone()
+    };
+}
+
+fn apply_build_action(
+    mut commands: Commands,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    action: Res<BuildAction>,
+    tower_cfg: Res<TowerConfig>,
+    mut currency: ResMut<Currency>,
+    levels: Query<&TowerLevel>,
+) {
+    if !mouse_input.just_pressed(MouseButton::Left) {
+        return;
+    }
+    match *action {
+        BuildAction::None => {}
+        BuildAction::Place { position, valid } => {
+            if !valid || !currency.try_spend(tower_cfg.build_cost) {
+                return;
+            }
+            commands.spawn((
+                Tower,
+                Transform::from_translation(position),
+                StateScoped(Screen::Gameplay),
+            ));
+        }
+        BuildAction::Upgrade { tower, cost } => {
+            let Some(cost) = cost else {
+                return;
+            };
+            let Ok(level) = levels.get(tower) else {
+                return;
+            };
+            if !currency.try_spend(cost) {
+                return;
+            }
+            commands.entity(tower).insert(TowerLevel(level.0 + 1));
+        }
+    }
+}
+
+fn spawn_build_hud(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Build HUD"),
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(20.0),
+            top: Val::Px(20.0),
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(10.0),
+            ..default()
+        },
+        Pickable::IGNORE,
+        StateScoped(Screen::Gameplay),
+        children![
+            (widget::label(""), CurrencyLabel),
+            (widget::label(""), BuildHintLabel),
+        ],
+    ));
+}
+
+fn update_build_hud(
+    currency: Res<Currency>,
+    build_mode: Res<State<BuildMode>>,
+    action: Res<BuildAction>,
+    tower_cfg: Res<TowerConfig>,
+    key_bindings: Res<KeyBindings>,
+    mut currency_label: Single<&mut Text, (With<CurrencyLabel>, Without<BuildHintLabel>)>,
+    mut hint_label: Single<&mut Text, (With<BuildHintLabel>, Without<CurrencyLabel>)>,
+) {
+    currency_label.0 = format!("Gold: {}", currency.0);
+    hint_label.0 = if !build_mode.get().0 {
+        format!("[{}] Build", key_name(key_bindings.build_mode))
+    } else {
+        match *action {
+            BuildAction::Upgrade {
+                cost: Some(cost), ..
+            } => format!("[Click] Upgrade tower ({cost} gold)"),
+            BuildAction::Upgrade { cost: None, .. } => "Tower fully upgraded".to_string(),
+            _ => format!("[Click] Place tower ({} gold)", tower_cfg.build_cost),
+        }
+    };
+}
@@ -0,0 +1,78 @@
+//! Camera bounds. A level puts [`CameraBounds`] on itself to keep the
+//! [`MainCamera`] looking at the level, from not too close or too far and
+//! never from underneath it, however the player orbits, pans or zooms.
+
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+use bevy_panorbit_camera::{PanOrbitCamera, PanOrbitCameraSystemSet};
+use smart_default::SmartDefault;
+use std::f32::consts::FRAC_PI_2;
+
+use super::MainCamera;
+
+/// Limits on where the camera looks and from where. Unbounded by default.
+#[auto_register_type]
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect, SmartDefault)]
+#[reflect(Component)]
+pub struct CameraBounds {
+    #[default(Vec3::NEG_INFINITY)]
+    pub min_focus: Vec3,
+    #[default(Vec3::INFINITY)]
+    pub max_focus: Vec3,
+    pub min_radius: f32,
+    #[default(f32::INFINITY)]
+    pub max_radius: f32,
+    /// Radians above the focus, `0.0` being level with it.
+    #[default(-FRAC_PI_2)]
+    pub min_pitch: f32,
+    #[default(FRAC_PI_2)]
+    pub max_pitch: f32,
+}
+
+impl CameraBounds {
+    pub fn clamp_focus(&self, focus: Vec3) -> Vec3 {
+        focus.clamp(self.min_focus, self.max_focus.max(self.min_focus))
+    }
+
+    pub fn clamp_radius(&self, radius: f32) -> f32 {
+        radius.clamp(self.min_radius, self.max_radius.max(self.min_radius))
+    }
+
+    pub fn clamp_pitch(&self, pitch: f32) -> f32 {
+        pitch.clamp(self.min_pitch, self.max_pitch.max(self.min_pitch))
+    }
+}
+
+#[auto_plugin(app=app)]
+pub(super) fn plugin(app: &mut App) {
+    // Before the orbit moves the camera towards its targets
+    app.add_systems(
+        PostUpdate,
+        clamp_camera_to_bounds.before(PanOrbitCameraSystemSet),
+    );
+}
+
+fn clamp_camera_to_bounds(
+    bounds: Option<Single<&CameraBounds>>,
+    mut cameras: Query<&mut PanOrbitCamera, With<MainCamera>>,
+) {
+    let Some(bounds) = bounds else {
+        return;
+    };
+    for mut camera in cameras.iter_mut() {
+        let focus = bounds.clamp_focus(camera.target_focus);
+        let radius = bounds.clamp_radius(camera.target_radius);
+        let pitch = bounds.clamp_pitch(camera.target_pitch);
+        if focus == camera.target_focus
+            && radius == camera.target_radius
+            && pitch == camera.target_pitch
+        {
+            continue;
+        }
+        camera.target_focus = focus;
+        camera.target_radius = radius;
+        camera.target_pitch = pitch;
+        // PanOrbitCamera only updates on input events unless forced to
+        camera.force_update = true;
+    }
+}
@@ -0,0 +1,97 @@
+//! Camera collision. Whenever terrain or a tower stands between the
+//! [`MainCamera`] and what it's looking at, the camera is pulled in front of
+//! it, easing back out once the view is clear. Like the shake, the pull is
+//! added on top of wherever [`PanOrbitCamera`] put the camera and taken off
+//! again at the start of the next frame.
+
+use avian3d::prelude::{SpatialQuery, SpatialQueryFilter};
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+use bevy_panorbit_camera::{PanOrbitCamera, PanOrbitCameraSystemSet};
+use smart_default::SmartDefault;
+
+use super::{MainCamera, shake};
+use crate::game::physics::layers::GameLayer;
+
+#[auto_register_type]
+#[derive(Component, Debug, Default, Copy, Clone, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct CameraCollision {
+    /// How far from the focus the camera was allowed last frame, if it was
+    /// pulled in.
+    pub pulled_distance: Option<f32>,
+    /// Offset added to the camera this frame, to be taken off the next.
+    applied_translation: Vec3,
+}
+
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, Clone, Reflect, SmartDefault)]
+#[reflect(Resource)]
+pub struct CameraCollisionConfig {
+    /// Gap kept between the camera and whatever blocks the view.
+    #[default(5.0)]
+    pub margin: f32,
+    /// Closest the camera gets to the focus, however much is in the way.
+    #[default(10.0)]
+    pub min_distance: f32,
+    /// How quickly the camera eases back out once clear, per second.
+    #[default(4.0)]
+    pub ease_out: f32,
+}
+
+#[auto_plugin(app=app)]
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(PreUpdate, undo_collision);
+    app.add_systems(
+        PostUpdate,
+        apply_collision
+            .after(PanOrbitCameraSystemSet)
+            .before(shake::apply_shake)
+            .before(TransformSystem::TransformPropagate),
+    );
+}
+
+fn undo_collision(mut cameras: Query<(&mut CameraCollision, &mut Transform), With<MainCamera>>) {
+    for (mut collision, mut transform) in cameras.iter_mut() {
+        if collision.applied_translation == Vec3::ZERO {
+            continue;
+        }
+        transform.translation -= collision.applied_translation;
+        collision.applied_translation = Vec3::ZERO;
+    }
+}
+
+fn apply_collision(
+    time: Res<Time>,
+    cfg: Res<CameraCollisionConfig>,
+    spatial_query: SpatialQuery,
+    mut cameras: Query<(&PanOrbitCamera, &mut CameraCollision, &mut Transform), With<MainCamera>>,
+) {
+    let filter = SpatialQueryFilter::from_mask([GameLayer::Terrain, GameLayer::Tower]);
+    for (camera, mut collision, mut transform) in cameras.iter_mut() {
+        let Ok((direction, distance)) = Dir3::new_and_length(transform.translation - camera.focus)
+        else {
+            continue;
+        };
+        // Not solid, so a focus inside a tower still sees out of it
+        let blocked = spatial_query
+            .cast_ray(camera.focus, direction, distance, false, &filter)
+            .map(|hit| (hit.distance - cfg.margin).max(cfg.min_distance.min(distance)));
+        // Pull in straight away, but ease back out so it doesn't pop
+        let ease = 1.0 - (-cfg.ease_out * time.delta_secs()).exp();
+        let eased = collision
+            .pulled_distance
+            .map(|pulled| pulled.lerp(distance, ease));
+        let allowed = match (blocked, eased) {
+            (Some(blocked), Some(eased)) => Some(blocked.min(eased)),
+            (blocked, eased) => blocked.or(eased),
+        };
+        collision.pulled_distance = allowed.filter(|&allowed| distance - allowed > 0.1);
+        let Some(allowed) = collision.pulled_distance else {
+            continue;
+        };
+        collision.applied_translation = direction * (allowed - distance);
+        transform.translation += collision.applied_translation;
+    }
+}
@@ -1,9 +1,15 @@
+pub mod bounds;
+pub mod collision;
+pub mod modes;
+pub mod shake;
+
 use bevy::core_pipeline::bloom::Bloom;
 use bevy::pbr::ShadowFilteringMethod;
 use bevy::prelude::*;
 use bevy_auto_plugin::auto_plugin::*;
 use bevy_panorbit_camera::PanOrbitCamera;
 use bevy_panorbit_camera::PanOrbitCameraPlugin;
+use smart_default::SmartDefault;
 
 #[auto_register_type]
 #[auto_name]
@@ -11,17 +17,68 @@ use bevy_panorbit_camera::PanOrbitCameraPlugin;
 #[reflect(Component)]
 #[require(PanOrbitCamera)]
 #[require(ShadowFilteringMethod::Hardware2x2)]
+#[require(shake::CameraShake)]
+#[require(collision::CameraCollision)]
 pub struct MainCamera;
 
+/// Something for the camera to look at. Which targets count is up to the
+/// [`CameraFraming`], and several are framed together around their centre
+/// weighted by `weight`.
 #[auto_register_type]
-#[derive(Component, Debug, Default, Clone, Copy, Reflect)]
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect, SmartDefault)]
 #[reflect(Component)]
 #[require(Transform)]
-pub struct CameraTarget;
+pub struct CameraTarget {
+    pub priority: i32,
+    #[default(1.0)]
+    pub weight: f32,
+}
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Reflect)]
+pub enum CameraFraming {
+    /// Looks at the highest priority targets only.
+    #[default]
+    HighestPriority,
+    /// Frames every target, whatever its priority.
+    Group,
+}
+
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, Clone, Reflect, SmartDefault)]
+#[reflect(Resource)]
+pub struct CameraTargetConfig {
+    pub framing: CameraFraming,
+    /// Room left around a group of targets, as a fraction of its size.
+    #[default(0.25)]
+    pub group_margin: f32,
+    /// Space each target takes up when framing a group.
+    #[default(20.0)]
+    pub target_radius: f32,
+}
+
+/// Where the [`CameraTarget`]s are, for the [`modes::CameraMode`]s that look
+/// at them.
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Resource)]
+pub struct CameraTargetFocus {
+    pub focus: Option<Vec3>,
+    /// How far the camera needs to be to fit every target in view, when
+    /// framing more than one.
+    pub min_radius: Option<f32>,
+}
 
 #[auto_plugin(app=app)]
 pub(crate) fn plugin(app: &mut App) {
     app.add_plugins(PanOrbitCameraPlugin);
+    app.add_plugins((
+        bounds::plugin,
+        collision::plugin,
+        modes::plugin,
+        shake::plugin,
+    ));
     app.add_systems(Startup, spawn_camera);
     app.add_systems(Update, update_camera_target);
     app.insert_resource(AmbientLight {
@@ -31,6 +88,12 @@ pub(crate) fn plugin(app: &mut App) {
     });
 }
 
+/// World-space ray under the cursor, if it's over the window.
+pub fn cursor_ray(window: &Window, camera: &Camera, camera_gt: &GlobalTransform) -> Option<Ray3d> {
+    let cursor = window.cursor_position()?;
+    camera.viewport_to_world(camera_gt, cursor).ok()
+}
+
 fn spawn_camera(mut commands: Commands) {
     commands.spawn((
         MainCamera,
@@ -49,21 +112,54 @@ fn spawn_camera(mut commands: Commands) {
 }
 
 fn update_camera_target(
-    mut pan_orbit_q: Single<Mut<PanOrbitCamera>>,
-    target_q: Single<
-        &GlobalTransform,
-        Or<(
-            Added<CameraTarget>,
-            (With<CameraTarget>, Changed<GlobalTransform>),
-        )>,
-    >,
+    cfg: Res<CameraTargetConfig>,
+    mut focus: ResMut<CameraTargetFocus>,
+    camera: Option<Single<&Projection, With<MainCamera>>>,
+    targets: Query<(&CameraTarget, &GlobalTransform)>,
 ) {
-    if pan_orbit_q.focus == target_q.translation() {
+    let Some(top_priority) = targets.iter().map(|(target, _)| target.priority).max() else {
+        // Keep looking where the last target was
         return;
-    }
-    pan_orbit_q.target_focus = target_q.translation();
-    // Whenever changing properties manually like this, it's necessary to force
-    // PanOrbitCamera to update this frame (by default it only updates when there are
-    // input events).
-    pan_orbit_q.force_update = true;
+    };
+    let framed = targets
+        .iter()
+        .filter(|(target, _)| {
+            cfg.framing == CameraFraming::Group || target.priority == top_priority
+        })
+        .map(|(target, gt)| (gt.translation(), target.weight.max(0.0)))
+        .collect::<Vec<_>>();
+
+    let total_weight = framed.iter().map(|&(_, weight)| weight).sum::<f32>();
+    let center = if total_weight > 0.0 {
+        framed
+            .iter()
+            .map(|&(position, weight)| position * weight)
+            .sum::<Vec3>()
+            / total_weight
+    } else {
+        framed.iter().map(|&(position, _)| position).sum::<Vec3>() / framed.len() as f32
+    };
+
+    let min_radius = (framed.len() > 1).then(|| {
+        let extent = framed
+            .iter()
+            .map(|&(position, _)| position.distance(center) + cfg.target_radius)
+            .fold(0.0, f32::max)
+            * (1.0 + cfg.group_margin);
+        // Fit the sphere around them in the narrower of the two fields of view
+        let half_fov = match camera.as_deref() {
+            Some(Projection::Perspective(perspective)) => {
+                let half_vertical = perspective.fov / 2.0;
+                let half_horizontal = (half_vertical.tan() * perspective.aspect_ratio).atan();
+                half_vertical.min(half_horizontal)
+            }
+            _ => std::f32::consts::FRAC_PI_8,
+        };
+        extent / half_fov.sin()
+    });
+
+    focus.set_if_neq(CameraTargetFocus {
+        focus: Some(center),
+        min_radius,
+    });
 }
@@ -0,0 +1,338 @@
+//! Automatic camera modes. The [`CameraMode`] state decides where the
+//! [`MainCamera`] looks and from how far and how high, easing between modes
+//! whenever it changes. `C` (by default) cycles through the modes the player
+//! can pick, and a spark jump cuts to a short [`CameraMode::Cinematic`] shot
+//! while following sparks. Orbiting, panning or zooming by hand holds the
+//! automatic camera off for [`CameraModeConfig::override_secs`] before it eases
+//! back in.
+
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+use bevy_panorbit_camera::PanOrbitCamera;
+use smart_default::SmartDefault;
+
+use super::bounds::CameraBounds;
+use super::{CameraTargetFocus, MainCamera};
+use crate::game::menus::Menu;
+use crate::game::screens::Screen;
+use crate::game::settings::binding_just_pressed;
+use crate::game::spark::{Spark, Zapping};
+
+#[auto_register_state_type]
+#[auto_init_state]
+#[derive(States, Debug, Default, Copy, Clone, PartialEq, Eq, Hash, Reflect)]
+pub enum CameraMode {
+    /// Follows the spark that jumped last, or the camera target without one.
+    #[default]
+    FollowSpark,
+    /// Stays on the camera target, leaving the angle and zoom to the player.
+    FreeOrbit,
+    /// Looks down on the camera target from high above.
+    TopDown,
+    /// Close-up of a spark jump, going back to the previous mode after.
+    Cinematic,
+}
+
+impl CameraMode {
+    /// Next mode the player can cycle to.
+    fn next(self) -> Self {
+        match self {
+            Self::FollowSpark => Self::FreeOrbit,
+            Self::FreeOrbit => Self::TopDown,
+            Self::TopDown | Self::Cinematic => Self::FollowSpark,
+        }
+    }
+}
+
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, Clone, Reflect, SmartDefault)]
+#[reflect(Resource)]
+pub struct CameraModeConfig {
+    #[default(0.8)]
+    pub transition_secs: f32,
+    #[default(EaseFunction::CubicInOut)]
+    pub transition_ease: EaseFunction,
+    /// How long the player's own camera input keeps the automatic camera off.
+    #[default(3.0)]
+    pub override_secs: f32,
+    #[default(400.0)]
+    pub follow_radius: f32,
+    #[default(0.6)]
+    pub follow_pitch: f32,
+    #[default(900.0)]
+    pub top_down_radius: f32,
+    /// Just short of straight down, where the orbit would flip over.
+    #[default(1.5)]
+    pub top_down_pitch: f32,
+    #[default(150.0)]
+    pub cinematic_radius: f32,
+    #[default(0.25)]
+    pub cinematic_pitch: f32,
+    #[default(1.5)]
+    pub cinematic_secs: f32,
+    /// How fast the cinematic shot circles the spark, in radians per second.
+    #[default(0.4)]
+    pub cinematic_yaw_speed: f32,
+}
+
+/// Where the camera looks and from where. Anything left `None` is up to the
+/// player.
+#[derive(Debug, Copy, Clone, PartialEq, Reflect)]
+struct Shot {
+    focus: Vec3,
+    radius: Option<f32>,
+    pitch: Option<f32>,
+    yaw: Option<f32>,
+}
+
+impl Shot {
+    fn of(camera: &PanOrbitCamera) -> Self {
+        Self {
+            focus: camera.target_focus,
+            radius: Some(camera.target_radius),
+            pitch: Some(camera.target_pitch),
+            yaw: Some(camera.target_yaw),
+        }
+    }
+
+    /// Part of the way from `from` to `self`, `t` going from `0.0` to `1.0`.
+    fn eased_from(self, from: Shot, t: f32) -> Self {
+        let lerp = |from: Option<f32>, to: Option<f32>| {
+            to.map(|to| from.map_or(to, |from| from.lerp(to, t)))
+        };
+        Self {
+            focus: from.focus.lerp(self.focus, t),
+            radius: lerp(from.radius, self.radius),
+            pitch: lerp(from.pitch, self.pitch),
+            yaw: lerp(from.yaw, self.yaw),
+        }
+    }
+
+    /// Kept within the level's `bounds`.
+    fn clamped(self, bounds: &CameraBounds) -> Self {
+        Self {
+            focus: bounds.clamp_focus(self.focus),
+            radius: self.radius.map(|radius| bounds.clamp_radius(radius)),
+            pitch: self.pitch.map(|pitch| bounds.clamp_pitch(pitch)),
+            yaw: self.yaw,
+        }
+    }
+
+    fn apply(self, camera: &mut PanOrbitCamera) {
+        camera.target_focus = self.focus;
+        if let Some(radius) = self.radius {
+            camera.target_radius = radius;
+        }
+        if let Some(pitch) = self.pitch {
+            camera.target_pitch = pitch;
+        }
+        if let Some(yaw) = self.yaw {
+            camera.target_yaw = yaw;
+        }
+        // PanOrbitCamera only updates on input events unless forced to
+        camera.force_update = true;
+    }
+
+    /// Whether the camera was moved away from this since it was applied.
+    fn was_moved(self, camera: &PanOrbitCamera) -> bool {
+        let moved = |shot: Option<f32>, current: f32| {
+            shot.is_some_and(|shot| (shot - current).abs() > 1e-3)
+        };
+        self.focus.distance_squared(camera.target_focus) > 1e-3
+            || moved(self.radius, camera.target_radius)
+            || moved(self.pitch, camera.target_pitch)
+            || moved(self.yaw, camera.target_yaw)
+    }
+}
+
+/// What the automatic camera is up to.
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, Clone, Reflect, Default)]
+#[reflect(Resource)]
+struct CameraRig {
+    /// Shot the current transition started from.
+    from: Option<Shot>,
+    transition: Timer,
+    /// Last shot applied, to tell when the player moves the camera.
+    applied: Option<Shot>,
+    /// Counts down while the player has control.
+    player_override: Timer,
+    /// Spark that jumped last.
+    spark: Option<Entity>,
+    /// Mode to go back to once the cinematic finishes.
+    resume: CameraMode,
+    cinematic: Timer,
+    /// Yaw the cinematic shot starts circling from.
+    cinematic_yaw: f32,
+}
+
+#[auto_plugin(app=app)]
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(cut_to_spark_jump);
+    app.add_systems(
+        Update,
+        cycle_camera_mode.run_if(
+            in_state(Screen::Gameplay)
+                .and(in_state(Menu::None))
+                .and(binding_just_pressed(|keys| keys.camera_mode)),
+        ),
+    );
+    app.add_systems(
+        Update,
+        (
+            start_transition.run_if(state_changed::<CameraMode>),
+            end_cinematic.run_if(in_state(CameraMode::Cinematic)),
+            update_camera_mode,
+        )
+            .chain()
+            .after(super::update_camera_target),
+    );
+}
+
+fn cycle_camera_mode(
+    mode: Res<State<CameraMode>>,
+    rig: Res<CameraRig>,
+    mut next: ResMut<NextState<CameraMode>>,
+) {
+    let current = match mode.get() {
+        CameraMode::Cinematic => rig.resume,
+        mode => *mode,
+    };
+    next.set(current.next());
+}
+
+fn cut_to_spark_jump(
+    trigger: Trigger<OnInsert, Zapping>,
+    cfg: Res<CameraModeConfig>,
+    mode: Res<State<CameraMode>>,
+    mut next: ResMut<NextState<CameraMode>>,
+    mut rig: ResMut<CameraRig>,
+    camera: Option<Single<&PanOrbitCamera, With<MainCamera>>>,
+) {
+    rig.spark = Some(trigger.target());
+    if *mode.get() != CameraMode::FollowSpark || !rig.player_override.finished() {
+        return;
+    }
+    rig.resume = CameraMode::FollowSpark;
+    rig.cinematic = Timer::from_seconds(cfg.cinematic_secs, TimerMode::Once);
+    rig.cinematic_yaw = camera.map_or(0.0, |camera| camera.target_yaw);
+    next.set(CameraMode::Cinematic);
+}
+
+fn start_transition(
+    cfg: Res<CameraModeConfig>,
+    mut rig: ResMut<CameraRig>,
+    camera: Option<Single<&PanOrbitCamera, With<MainCamera>>>,
+) {
+    rig.from = camera.map(|camera| Shot::of(&camera));
+    rig.transition = Timer::from_seconds(cfg.transition_secs, TimerMode::Once);
+}
+
+fn end_cinematic(
+    time: Res<Time>,
+    mut rig: ResMut<CameraRig>,
+    mut next: ResMut<NextState<CameraMode>>,
+) {
+    if rig.cinematic.tick(time.delta()).just_finished() {
+        next.set(rig.resume);
+    }
+}
+
+fn update_camera_mode(
+    time: Res<Time>,
+    cfg: Res<CameraModeConfig>,
+    mode: Res<State<CameraMode>>,
+    target_focus: Res<CameraTargetFocus>,
+    bounds: Option<Single<&CameraBounds>>,
+    mut rig: ResMut<CameraRig>,
+    mut camera: Option<Single<&mut PanOrbitCamera, With<MainCamera>>>,
+    sparks: Query<&GlobalTransform, With<Spark>>,
+) {
+    let Some(camera) = camera.as_deref_mut() else {
+        return;
+    };
+
+    // Hold off while the player moves the camera, then ease back in
+    if rig.applied.is_some_and(|applied| applied.was_moved(camera)) {
+        rig.player_override = Timer::from_seconds(cfg.override_secs, TimerMode::Once);
+        rig.applied = None;
+    }
+    if !rig.player_override.finished() {
+        if rig.player_override.tick(time.delta()).just_finished() {
+            rig.from = Some(Shot::of(camera));
+            rig.transition = Timer::from_seconds(cfg.transition_secs, TimerMode::Once);
+        } else {
+            return;
+        }
+    }
+
+    let spark_focus = rig
+        .spark
+        .and_then(|spark| sparks.get(spark).ok())
+        .or_else(|| sparks.iter().next())
+        .map(GlobalTransform::translation);
+    let focus = match mode.get() {
+        CameraMode::FollowSpark | CameraMode::Cinematic => spark_focus.or(target_focus.focus),
+        CameraMode::FreeOrbit | CameraMode::TopDown => target_focus.focus,
+    };
+    let Some(focus) = focus else {
+        return;
+    };
+    let shot = match mode.get() {
+        CameraMode::FollowSpark => Shot {
+            focus,
+            radius: Some(
+                target_focus
+                    .min_radius
+                    .map_or(cfg.follow_radius, |min| min.max(cfg.follow_radius)),
+            ),
+            pitch: Some(cfg.follow_pitch),
+            yaw: None,
+        },
+        // Zoom is up to the player, only pulled out as far as needed to fit
+        // every target in
+        CameraMode::FreeOrbit => Shot {
+            focus,
+            radius: target_focus
+                .min_radius
+                .map(|min| camera.target_radius.max(min)),
+            pitch: None,
+            yaw: None,
+        },
+        CameraMode::TopDown => Shot {
+            focus,
+            radius: Some(
+                target_focus
+                    .min_radius
+                    .map_or(cfg.top_down_radius, |min| min.max(cfg.top_down_radius)),
+            ),
+            pitch: Some(cfg.top_down_pitch),
+            yaw: None,
+        },
+        CameraMode::Cinematic => Shot {
+            focus,
+            radius: Some(cfg.cinematic_radius),
+            pitch: Some(cfg.cinematic_pitch),
+            yaw: Some(rig.cinematic_yaw + rig.cinematic.elapsed_secs() * cfg.cinematic_yaw_speed),
+        },
+    };
+
+    rig.transition.tick(time.delta());
+    let shot = match rig.from {
+        Some(from) if !rig.transition.finished() => {
+            let t = cfg
+                .transition_ease
+                .sample_clamped(rig.transition.fraction());
+            shot.eased_from(from, t)
+        }
+        _ => shot,
+    };
+    // Clamped here rather than after, which would look like the player moved it
+    let shot = bounds.map_or(shot, |bounds| shot.clamped(&bounds));
+    if rig.applied != Some(shot) {
+        shot.apply(camera);
+        rig.applied = Some(shot);
+    }
+}
@@ -0,0 +1,174 @@
+//! Screen shake. Hard hits add trauma to the [`MainCamera`]'s [`CameraShake`],
+//! which dies down over time and shakes the camera by its square, scaled by
+//! [`Accessibility::camera_shake`]. The shake is added on top of wherever
+//! [`PanOrbitCamera`](bevy_panorbit_camera::PanOrbitCamera) put the camera and
+//! taken off again at the start of the next frame, so the orbit never sees it.
+
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+use bevy_panorbit_camera::PanOrbitCameraSystemSet;
+use smart_default::SmartDefault;
+
+use super::MainCamera;
+use crate::game::health::{AdjustHp, Dead};
+use crate::game::prefabs::enemy::Enemy;
+use crate::game::prefabs::tower::Tower;
+use crate::game::settings::Accessibility;
+use crate::game::spark::{Spark, Zapping};
+
+#[auto_register_type]
+#[derive(Component, Debug, Default, Copy, Clone, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct CameraShake {
+    /// From `0.0` to `1.0`.
+    pub trauma: f32,
+    /// Offset added to the camera this frame, to be taken off the next.
+    applied_translation: Vec3,
+    applied_rotation: Quat,
+}
+
+/// Shakes the camera by `0`, added onto its trauma.
+#[auto_register_type]
+#[auto_add_event]
+#[derive(Event, Debug, Copy, Clone, Reflect)]
+pub struct AddTrauma(pub f32);
+
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, Clone, Reflect, SmartDefault)]
+#[reflect(Resource)]
+pub struct CameraShakeConfig {
+    /// Trauma lost per second.
+    #[default(1.2)]
+    pub decay_per_second: f32,
+    /// Furthest the camera moves at full trauma.
+    #[default(6.0)]
+    pub max_offset: f32,
+    #[default(2.0)]
+    pub max_angle_deg: f32,
+    /// How quickly the shake changes direction.
+    #[default(18.0)]
+    pub frequency: f32,
+    #[default(0.25)]
+    pub spark_jump: f32,
+    #[default(0.5)]
+    pub spark_discharge: f32,
+    #[default(0.3)]
+    pub tower_hit: f32,
+    #[default(0.15)]
+    pub kill: f32,
+}
+
+#[auto_plugin(app=app)]
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(shake_on_spark_jump);
+    app.add_observer(shake_on_death);
+    app.add_systems(PreUpdate, undo_shake);
+    app.add_systems(Update, (shake_on_tower_hit, add_trauma).chain());
+    app.add_systems(
+        PostUpdate,
+        apply_shake
+            .after(PanOrbitCameraSystemSet)
+            .before(TransformSystem::TransformPropagate),
+    );
+}
+
+fn shake_on_spark_jump(
+    _: Trigger<OnInsert, Zapping>,
+    cfg: Res<CameraShakeConfig>,
+    mut trauma: EventWriter<AddTrauma>,
+) {
+    trauma.write(AddTrauma(cfg.spark_jump));
+}
+
+/// Sparks discharge as they die, and enemies dying are kills.
+fn shake_on_death(
+    trigger: Trigger<OnAdd, Dead>,
+    cfg: Res<CameraShakeConfig>,
+    sparks: Query<(), With<Spark>>,
+    enemies: Query<(), With<Enemy>>,
+    mut trauma: EventWriter<AddTrauma>,
+) {
+    if sparks.contains(trigger.target()) {
+        trauma.write(AddTrauma(cfg.spark_discharge));
+    } else if enemies.contains(trigger.target()) {
+        trauma.write(AddTrauma(cfg.kill));
+    }
+}
+
+fn shake_on_tower_hit(
+    mut adjust_hp_events: EventReader<AdjustHp>,
+    cfg: Res<CameraShakeConfig>,
+    towers: Query<(), With<Tower>>,
+    parents: Query<&ChildOf>,
+    mut trauma: EventWriter<AddTrauma>,
+) {
+    // Whatever's on top of a tower, like the wizard, counts as the tower
+    let hit = adjust_hp_events.read().any(|event| {
+        event.amount < 0.0
+            && std::iter::once(event.target)
+                .chain(parents.iter_ancestors(event.target))
+                .any(|entity| towers.contains(entity))
+    });
+    if hit {
+        trauma.write(AddTrauma(cfg.tower_hit));
+    }
+}
+
+fn add_trauma(
+    time: Res<Time>,
+    cfg: Res<CameraShakeConfig>,
+    mut events: EventReader<AddTrauma>,
+    mut shakes: Query<&mut CameraShake, With<MainCamera>>,
+) {
+    let added = events.read().map(|AddTrauma(trauma)| trauma).sum::<f32>();
+    for mut shake in shakes.iter_mut() {
+        let trauma = shake.trauma + added - cfg.decay_per_second * time.delta_secs();
+        shake.trauma = trauma.clamp(0.0, 1.0);
+    }
+}
+
+fn undo_shake(mut cameras: Query<(&mut CameraShake, &mut Transform), With<MainCamera>>) {
+    for (mut shake, mut transform) in cameras.iter_mut() {
+        if shake.applied_translation == Vec3::ZERO && shake.applied_rotation == Quat::IDENTITY {
+            continue;
+        }
+        transform.translation -= shake.applied_translation;
+        transform.rotation *= shake.applied_rotation.inverse();
+        shake.applied_translation = Vec3::ZERO;
+        shake.applied_rotation = Quat::IDENTITY;
+    }
+}
+
+pub(super) fn apply_shake(
+    time: Res<Time>,
+    cfg: Res<CameraShakeConfig>,
+    accessibility: Res<Accessibility>,
+    mut cameras: Query<(&mut CameraShake, &mut Transform), With<MainCamera>>,
+) {
+    let t = time.elapsed_secs() * cfg.frequency;
+    for (mut shake, mut transform) in cameras.iter_mut() {
+        let amount = shake.trauma * shake.trauma * accessibility.camera_shake.clamp(0.0, 1.0);
+        if amount <= 0.0 {
+            continue;
+        }
+        let offset = Vec3::new(noise(0.0, t), noise(10.0, t), 0.0) * cfg.max_offset * amount;
+        let max_angle = cfg.max_angle_deg.to_radians() * amount;
+        let rotation = Quat::from_euler(
+            EulerRot::YXZ,
+            noise(20.0, t) * max_angle,
+            noise(30.0, t) * max_angle,
+            noise(40.0, t) * max_angle,
+        );
+        // Sideways and up from the camera's point of view
+        shake.applied_translation = transform.rotation * offset;
+        shake.applied_rotation = rotation;
+        transform.translation += shake.applied_translation;
+        transform.rotation *= rotation;
+    }
+}
+
+/// Smooth noise from `-1.0` to `1.0`, different for every `seed`.
+fn noise(seed: f32, t: f32) -> f32 {
+    (t + seed).sin() * 0.6 + (t * 2.3 + seed * 1.7).sin() * 0.3 + (t * 5.1 + seed * 3.1).sin() * 0.1
+}
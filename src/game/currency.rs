@@ -0,0 +1,53 @@
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+use smart_default::SmartDefault;
+
+use crate::game::health::Dead;
+use crate::game::screens::Screen;
+
+/// Gold available to spend on building and upgrading.
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, Copy, Clone, PartialEq, Eq, SmartDefault, Reflect)]
+#[reflect(Resource)]
+pub struct Currency(#[default(STARTING_CURRENCY)] pub u32);
+
+pub const STARTING_CURRENCY: u32 = 100;
+
+impl Currency {
+    /// Spends `amount` if there's enough, returning whether it was spent.
+    pub fn try_spend(&mut self, amount: u32) -> bool {
+        let Some(remaining) = self.0.checked_sub(amount) else {
+            return false;
+        };
+        self.0 = remaining;
+        true
+    }
+}
+
+/// Currency awarded when this entity dies.
+#[auto_register_type]
+#[derive(Component, Debug, Copy, Clone, Reflect)]
+#[reflect(Component)]
+pub struct Bounty(pub u32);
+
+#[auto_plugin(app=app)]
+pub fn plugin(app: &mut App) {
+    app.add_observer(award_bounty);
+    app.add_systems(OnEnter(Screen::Gameplay), reset_currency);
+}
+
+fn award_bounty(
+    tr: Trigger<OnAdd, Dead>,
+    bounties: Query<&Bounty>,
+    mut currency: ResMut<Currency>,
+) {
+    let Ok(bounty) = bounties.get(tr.target()) else {
+        return;
+    };
+    currency.0 += bounty.0;
+}
+
+fn reset_currency(mut currency: ResMut<Currency>) {
+    *currency = Currency::default();
+}
@@ -1,14 +1,27 @@
 use bevy::{ecs::schedule::ScheduleLabel, prelude::*};
 
+use crate::game::pause_controller::PausableSystems;
+
 #[derive(Component)]
 struct DespawnMarker;
 
 #[derive(Event)]
 pub struct DespawnDelayed;
 
+/// Triggers [`DespawnDelayed`] once the timer finishes.
+#[derive(Component)]
+pub struct DespawnAfter(pub Timer);
+
+impl DespawnAfter {
+    pub fn secs(secs: f32) -> Self {
+        Self(Timer::from_seconds(secs, TimerMode::Once))
+    }
+}
+
 pub fn plugin<T: ScheduleLabel + Default>(app: &mut App) {
     app.add_observer(handle_despawn_entity)
-        .add_systems(T::default(), despawn);
+        .add_systems(T::default(), despawn)
+        .add_systems(Update, tick_despawn_after.in_set(PausableSystems));
 }
 
 fn handle_despawn_entity(tr: Trigger<DespawnDelayed>, mut commands: Commands) {
@@ -20,3 +33,15 @@ fn despawn(qs: Query<Entity, With<DespawnMarker>>, mut commands: Commands) {
         commands.entity(e).try_despawn();
     }
 }
+
+fn tick_despawn_after(
+    qs: Query<(Entity, &mut DespawnAfter), Without<DespawnMarker>>,
+    time: Res<Time>,
+    mut commands: Commands,
+) {
+    for (e, mut despawn_after) in qs {
+        if despawn_after.0.tick(time.delta()).just_finished() {
+            commands.entity(e).trigger(DespawnDelayed);
+        }
+    }
+}
@@ -0,0 +1,112 @@
+//! Lightning bolt stress test. `F3` spawns a grid of lightning balls, each
+//! surrounded by conduits to arc into, and `F4` switches between regenerating
+//! their bolts every frame and at [`DEFAULT_LIGHTNING_BALL_BOLT_REFRESH_HZ`].
+//! Compare the ms/frame of the FPS overlay between the two.
+
+use avian3d::prelude::Collider;
+use bevy::input::common_conditions::input_just_pressed;
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+use smart_default::SmartDefault;
+use std::f32::consts::TAU;
+
+use crate::game::effects::lightning_ball::{
+    DEFAULT_LIGHTNING_BALL_BOLT_REFRESH_HZ, LightningBall, LightningBallConduit,
+    LightningBallConfig, LightningBallDischarge,
+};
+use crate::game::screens::Screen;
+
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, Copy, Clone, Reflect, SmartDefault)]
+#[reflect(Resource)]
+struct BoltBench {
+    #[default(8)]
+    grid_size: usize,
+    #[default(60.0)]
+    spacing: f32,
+    #[default(150.0)]
+    height: f32,
+    #[default(6)]
+    conduits_per_ball: usize,
+    #[default(15.0)]
+    conduit_distance: f32,
+    refresh_every_frame: bool,
+}
+
+#[auto_plugin(app=app)]
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            spawn_bench.run_if(input_just_pressed(KeyCode::F3)),
+            toggle_refresh.run_if(input_just_pressed(KeyCode::F4)),
+        )
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+fn spawn_bench(mut commands: Commands, bench: Res<BoltBench>) {
+    let half_extent = (bench.grid_size.saturating_sub(1)) as f32 * bench.spacing / 2.0;
+    for x in 0..bench.grid_size {
+        for z in 0..bench.grid_size {
+            let position = Vec3::new(
+                x as f32 * bench.spacing - half_extent,
+                bench.height,
+                z as f32 * bench.spacing - half_extent,
+            );
+            commands.spawn((
+                Name::new("Bench Lightning Ball"),
+                LightningBall,
+                LightningBallConfig {
+                    bolt_refresh_hz: refresh_hz(&bench),
+                    ..default()
+                },
+                LightningBallDischarge {
+                    arcs: bench.conduits_per_ball,
+                    ..default()
+                },
+                Transform::from_translation(position),
+                StateScoped(Screen::Gameplay),
+            ));
+            // Not children of the ball, or their colliders would join its body
+            // and never be picked up by its sensor
+            for i in 0..bench.conduits_per_ball {
+                let angle = i as f32 / bench.conduits_per_ball as f32 * TAU;
+                let offset =
+                    Vec3::new(angle.cos(), -0.5, angle.sin()).normalize() * bench.conduit_distance;
+                commands.spawn((
+                    Name::new("Bench Conduit"),
+                    LightningBallConduit,
+                    Collider::sphere(1.0),
+                    Transform::from_translation(position + offset),
+                    StateScoped(Screen::Gameplay),
+                ));
+            }
+        }
+    }
+    info!(
+        "bolt bench: spawned {} lightning balls",
+        bench.grid_size * bench.grid_size
+    );
+}
+
+fn toggle_refresh(
+    mut bench: ResMut<BoltBench>,
+    mut configs: Query<&mut LightningBallConfig, With<LightningBall>>,
+) {
+    bench.refresh_every_frame = !bench.refresh_every_frame;
+    let hz = refresh_hz(&bench);
+    for mut config in configs.iter_mut() {
+        config.bolt_refresh_hz = hz;
+    }
+    info!("bolt bench: refreshing bolts at {hz} Hz (0 is every frame)");
+}
+
+fn refresh_hz(bench: &BoltBench) -> f32 {
+    if bench.refresh_every_frame {
+        0.0
+    } else {
+        DEFAULT_LIGHTNING_BALL_BOLT_REFRESH_HZ
+    }
+}
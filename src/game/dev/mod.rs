@@ -1,3 +1,4 @@
+mod bolt_bench;
 mod fps;
 mod inspector_ui;
 mod selection;
@@ -8,7 +9,7 @@ use bevy_auto_plugin::auto_plugin::*;
 
 #[auto_plugin(app=app)]
 pub(crate) fn plugin(app: &mut App) {
-    app.add_plugins(fps::plugin);
+    app.add_plugins((fps::plugin, bolt_bench::plugin));
     #[cfg(feature = "inspector_ui")]
     {
         app.add_plugins(inspector_ui::plugin);
@@ -0,0 +1,443 @@
+//! Lightning bolt geometry, free of any ECS state. Every generator takes the
+//! [`Rng`] to draw from, so the same seed always gives the same bolt, and
+//! writes into existing buffers rather than allocating new ones.
+
+use bevy::prelude::*;
+use rand::Rng;
+use std::f32::consts::TAU;
+use std::ops::RangeInclusive;
+
+use crate::game::rng::sphere::sample_point_on_sphere;
+
+pub type Polyline = Vec<Vec3>;
+
+/// Polylines that keep their allocations when cleared, so regenerating bolts
+/// into them stops allocating once they're big enough.
+#[derive(Debug, Default, Clone)]
+pub struct Polylines {
+    lines: Vec<Polyline>,
+    len: usize,
+    /// Where [`branching_bolt_into`] forks, used as a stack while it recurses.
+    forks: Vec<Vec3>,
+}
+
+impl Polylines {
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Starts a new, empty polyline.
+    pub fn push(&mut self) -> &mut Polyline {
+        if self.len == self.lines.len() {
+            self.lines.push(Polyline::new());
+        }
+        let line = &mut self.lines[self.len];
+        line.clear();
+        self.len += 1;
+        line
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &[Vec3]> {
+        self.lines[..self.len].iter().map(Vec::as_slice)
+    }
+}
+
+/// Settings for [`midpoint_displacement_into`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Displacement {
+    /// Every generation doubles the number of segments, up to
+    /// [`Displacement::MAX_GENERATIONS`].
+    pub generations: u32,
+    /// Furthest a midpoint can be pushed, as a fraction of its segment's length.
+    pub roughness: f32,
+}
+
+impl Displacement {
+    pub const MAX_GENERATIONS: u32 = 12;
+
+    /// Number of segments in every bolt made with these settings.
+    pub fn segments(&self) -> usize {
+        1 << self.generations.min(Self::MAX_GENERATIONS)
+    }
+}
+
+/// Settings for [`branching_bolt_into`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Branching {
+    /// Branches of branches, up to this many levels deep.
+    pub max_depth: u32,
+    /// Chance of a branch forking off each interior point.
+    pub probability: f32,
+    /// Length of a branch, as a fraction of the distance left to the end.
+    pub length: f32,
+    /// Furthest a branch can turn away from heading towards the end.
+    pub max_angle: f32,
+}
+
+/// Settings for [`sphere_crawl_into`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SphereCrawl {
+    /// Each point lands at a random distance in this range from the center.
+    pub radius_range: RangeInclusive<f32>,
+    pub segments: usize,
+    pub segment_length: f32,
+    /// Furthest the crawl turns at each point.
+    pub max_angle: f32,
+}
+
+/// Jagged line from `start` to `end` written over `out`, made by repeatedly
+/// splitting every segment and pushing its midpoint sideways.
+pub fn midpoint_displacement_into<R: Rng>(
+    rng: &mut R,
+    start: Vec3,
+    end: Vec3,
+    displacement: &Displacement,
+    out: &mut Polyline,
+) {
+    let segments = displacement.segments();
+    out.clear();
+    out.resize(segments + 1, end);
+    out[0] = start;
+    // Split every segment of a generation, left to right
+    let mut step = segments;
+    while step > 1 {
+        let half = step / 2;
+        for i in (half..segments).step_by(step) {
+            out[i] = displaced_midpoint(rng, out[i - half], out[i + half], displacement);
+        }
+        step = half;
+    }
+}
+
+fn displaced_midpoint<R: Rng>(rng: &mut R, a: Vec3, b: Vec3, displacement: &Displacement) -> Vec3 {
+    let length = a.distance(b);
+    if length <= f32::EPSILON {
+        return a;
+    }
+    let direction = (b - a) / length;
+    let max_offset = length * displacement.roughness;
+    let offset = rng.random_range(-max_offset..=max_offset);
+    a.midpoint(b) + random_perpendicular(rng, direction) * offset
+}
+
+/// A [`midpoint_displacement_into`] bolt with branches forking off it, and off
+/// those branches up to [`Branching::max_depth`], added to the end of `out`.
+/// The main bolt comes first.
+pub fn branching_bolt_into<R: Rng>(
+    rng: &mut R,
+    start: Vec3,
+    end: Vec3,
+    displacement: &Displacement,
+    branching: &Branching,
+    out: &mut Polylines,
+) {
+    branch(rng, start, end, displacement, branching, 0, out);
+}
+
+fn branch<R: Rng>(
+    rng: &mut R,
+    start: Vec3,
+    end: Vec3,
+    displacement: &Displacement,
+    branching: &Branching,
+    depth: u32,
+    out: &mut Polylines,
+) {
+    let index = out.len();
+    midpoint_displacement_into(rng, start, end, displacement, out.push());
+    // This branch's forks go on top of its parents', which stay put below
+    let forks_start = out.forks.len();
+    if depth < branching.max_depth {
+        let probability = branching.probability.clamp(0.0, 1.0) as f64;
+        let points = &out.lines[index];
+        out.forks.extend(
+            points[1..points.len() - 1]
+                .iter()
+                .copied()
+                .filter(|_| rng.random_bool(probability)),
+        );
+    }
+    let forks_end = out.forks.len();
+
+    // Branches are shorter so they get a generation less detail
+    let displacement = Displacement {
+        generations: displacement.generations.saturating_sub(1).max(1),
+        ..*displacement
+    };
+    for fork_index in forks_start..forks_end {
+        let fork = out.forks[fork_index];
+        let Some(towards_end) = (end - fork).try_normalize() else {
+            continue;
+        };
+        let angle = rng.random_range(-branching.max_angle..=branching.max_angle);
+        let axis = random_perpendicular(rng, towards_end);
+        let direction = Quat::from_axis_angle(axis, angle) * towards_end;
+        let length = fork.distance(end) * branching.length;
+        branch(
+            rng,
+            fork,
+            fork + direction * length,
+            &displacement,
+            branching,
+            depth + 1,
+            out,
+        );
+    }
+    out.forks.truncate(forks_start);
+}
+
+/// Wanders across the surface of a sphere centered on the origin from a random
+/// starting point, turning by up to [`SphereCrawl::max_angle`] at each step,
+/// written over `out`.
+pub fn sphere_crawl_into<R: Rng>(rng: &mut R, crawl: &SphereCrawl, out: &mut Polyline) {
+    let (radius_min, radius_max) = (*crawl.radius_range.start(), *crawl.radius_range.end());
+    let start = sample_point_on_sphere(rng) * (radius_min + radius_max) / 2.0;
+    let normal = start.normalize();
+    let (tangent, bitangent) = normal.any_orthonormal_pair();
+    let phi = rng.random_range(0.0..TAU);
+    let mut direction = tangent * phi.cos() + bitangent * phi.sin();
+
+    out.clear();
+    out.push(start);
+    let mut last = start;
+    for _ in 0..crawl.segments {
+        // Wiggle the direction within the tangent plane
+        let theta = rng.random_range(-crawl.max_angle..=crawl.max_angle);
+        direction = (Quat::from_axis_angle(normal, theta) * direction).normalize();
+        // Step forward and project back onto the sphere at a random radius
+        let radius = rng.random_range(radius_min..=radius_max);
+        last = (last + direction * crawl.segment_length).normalize() * radius;
+        out.push(last);
+    }
+}
+
+/// Random point on the half of a sphere centered on the origin that faces the
+/// normalized `pole`.
+pub fn hemisphere_point<R: Rng>(rng: &mut R, pole: Vec3, radius: f32) -> Vec3 {
+    let (perp1, perp2) = pole.any_orthonormal_pair();
+    let phi = rng.random_range(0.0..TAU);
+    let cos_theta = rng.random_range(0.0..=1.0f32);
+    let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
+    (perp1 * (sin_theta * phi.cos()) + perp2 * (sin_theta * phi.sin()) + pole * cos_theta) * radius
+}
+
+/// Random unit vector at right angles to the normalized `direction`.
+fn random_perpendicular<R: Rng>(rng: &mut R, direction: Vec3) -> Vec3 {
+    let (perp1, perp2) = direction.any_orthonormal_pair();
+    let angle = rng.random_range(0.0..TAU);
+    perp1 * angle.cos() + perp2 * angle.sin()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::rng::{Prng, ZERO_SEED};
+    use rand::SeedableRng;
+    use std::f32::consts::FRAC_PI_4;
+
+    const DISPLACEMENT: Displacement = Displacement {
+        generations: 2,
+        roughness: 0.2,
+    };
+
+    fn rng() -> Prng {
+        Prng::from_seed(ZERO_SEED)
+    }
+
+    fn midpoint_displacement(
+        rng: &mut Prng,
+        start: Vec3,
+        end: Vec3,
+        displacement: &Displacement,
+    ) -> Polyline {
+        let mut points = Polyline::new();
+        midpoint_displacement_into(rng, start, end, displacement, &mut points);
+        points
+    }
+
+    fn branching_bolt(
+        rng: &mut Prng,
+        start: Vec3,
+        end: Vec3,
+        displacement: &Displacement,
+        branching: &Branching,
+    ) -> Vec<Polyline> {
+        let mut bolts = Polylines::default();
+        branching_bolt_into(rng, start, end, displacement, branching, &mut bolts);
+        bolts.iter().map(<[Vec3]>::to_vec).collect()
+    }
+
+    fn sphere_crawl(rng: &mut Prng, crawl: &SphereCrawl) -> Polyline {
+        let mut points = Polyline::new();
+        sphere_crawl_into(rng, crawl, &mut points);
+        points
+    }
+
+    fn assert_polyline_eq(actual: &[Vec3], expected: &[Vec3]) {
+        assert_eq!(actual.len(), expected.len(), "{actual:?} != {expected:?}");
+        for (a, e) in actual.iter().zip(expected) {
+            assert!(a.abs_diff_eq(*e, 1e-4), "{actual:?} != {expected:?}");
+        }
+    }
+
+    #[test]
+    fn midpoint_displacement_is_pinned() {
+        let points = midpoint_displacement(
+            &mut rng(),
+            Vec3::ZERO,
+            Vec3::new(10.0, 0.0, 0.0),
+            &DISPLACEMENT,
+        );
+        assert_polyline_eq(
+            &points,
+            &[
+                Vec3::new(0.0, 0.0, 0.0),
+                Vec3::new(2.5072107, -0.09721471, 0.016209178),
+                Vec3::new(5.0, 0.118388645, -0.1717675),
+                Vec3::new(7.4922514, 0.085179254, 0.15758488),
+                Vec3::new(10.0, 0.0, 0.0),
+            ],
+        );
+    }
+
+    #[test]
+    fn midpoint_displacement_keeps_degenerate_bolts() {
+        let points = midpoint_displacement(&mut rng(), Vec3::ONE, Vec3::ONE, &DISPLACEMENT);
+        assert_eq!(points, vec![Vec3::ONE; DISPLACEMENT.segments() + 1]);
+    }
+
+    #[test]
+    fn branching_bolt_is_pinned() {
+        let branching = Branching {
+            max_depth: 2,
+            probability: 0.5,
+            length: 0.5,
+            max_angle: FRAC_PI_4,
+        };
+        let bolts = branching_bolt(
+            &mut rng(),
+            Vec3::ZERO,
+            Vec3::new(0.0, -10.0, 0.0),
+            &DISPLACEMENT,
+            &branching,
+        );
+        assert_eq!(bolts.len(), 3);
+        assert_polyline_eq(
+            &bolts[0],
+            &[
+                Vec3::new(0.0, 0.0, 0.0),
+                Vec3::new(0.1967535, -2.4926324, 0.20950189),
+                Vec3::new(0.1717675, -5.0, 0.118388645),
+                Vec3::new(-0.15571292, -7.4926443, 0.09905882),
+                Vec3::new(0.0, -10.0, 0.0),
+            ],
+        );
+        // Forks off the main bolt
+        assert_polyline_eq(
+            &bolts[1],
+            &[
+                Vec3::new(0.1717675, -5.0, 0.118388645),
+                Vec3::new(1.0184925, -5.974577, 0.29415178),
+                Vec3::new(1.6427902, -6.995356, -0.2215029),
+            ],
+        );
+        // Forks off the first branch
+        assert_polyline_eq(
+            &bolts[2],
+            &[
+                Vec3::new(1.0184925, -5.974577, 0.29415178),
+                Vec3::new(1.2650573, -6.159567, 0.15152669),
+                Vec3::new(1.3933979, -6.4690456, 0.09576845),
+            ],
+        );
+    }
+
+    #[test]
+    fn branching_bolt_without_branches() {
+        let branching = Branching {
+            max_depth: 0,
+            probability: 1.0,
+            length: 0.5,
+            max_angle: FRAC_PI_4,
+        };
+        let bolts = branching_bolt(
+            &mut rng(),
+            Vec3::ZERO,
+            Vec3::new(0.0, -10.0, 0.0),
+            &DISPLACEMENT,
+            &branching,
+        );
+        assert_eq!(bolts.len(), 1);
+    }
+
+    #[test]
+    fn sphere_crawl_is_pinned() {
+        let crawl = SphereCrawl {
+            radius_range: 1.0..=1.1,
+            segments: 3,
+            segment_length: 0.5,
+            max_angle: FRAC_PI_4,
+        };
+        let points = sphere_crawl(&mut rng(), &crawl);
+        assert_polyline_eq(
+            &points,
+            &[
+                Vec3::new(0.859826, 0.59262455, 0.10952253),
+                Vec3::new(0.60030836, 0.70155627, 0.47370705),
+                Vec3::new(0.48345357, 0.46832407, 0.75892746),
+                Vec3::new(0.40841958, 0.23209548, 0.9354398),
+            ],
+        );
+        for point in &points {
+            assert!(crawl.radius_range.contains(&point.length()));
+        }
+    }
+
+    #[test]
+    fn hemisphere_point_is_pinned() {
+        let point = hemisphere_point(&mut rng(), Vec3::Y, 2.0);
+        assert_polyline_eq(&[point], &[Vec3::new(-1.8848222, 0.19208932, 0.640739)]);
+        assert!(point.y >= 0.0);
+    }
+
+    #[test]
+    fn regenerating_into_buffers_matches() {
+        let branching = Branching {
+            max_depth: 2,
+            probability: 0.5,
+            length: 0.5,
+            max_angle: FRAC_PI_4,
+        };
+        let end = Vec3::new(0.0, -10.0, 0.0);
+        let expected = branching_bolt(&mut rng(), Vec3::ZERO, end, &DISPLACEMENT, &branching);
+
+        let mut buffers = Polylines::default();
+        for _ in 0..3 {
+            buffers.clear();
+            branching_bolt_into(
+                &mut rng(),
+                Vec3::ZERO,
+                end,
+                &DISPLACEMENT,
+                &branching,
+                &mut buffers,
+            );
+            assert_eq!(buffers.iter().collect::<Vec<_>>(), expected);
+        }
+    }
+
+    #[test]
+    fn same_seed_same_bolt() {
+        let a = midpoint_displacement(&mut rng(), Vec3::ZERO, Vec3::X, &DISPLACEMENT);
+        let b = midpoint_displacement(&mut rng(), Vec3::ZERO, Vec3::X, &DISPLACEMENT);
+        assert_eq!(a, b);
+    }
+}
@@ -0,0 +1,274 @@
+//! Lightning bolt renderer. The polylines of every [`BoltGroup`] are drawn as
+//! camera-facing ribbons that taper towards their ends, batched into a single
+//! mesh with an additive unlit material whose colors go well above `1.0`, so
+//! they glow under the [`MainCamera`] HDR and bloom.
+
+pub mod generator;
+
+use bevy::color::palettes::css::SKY_BLUE;
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology, VertexAttributeValues};
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::view::NoFrustumCulling;
+use bevy_auto_plugin::auto_plugin::*;
+use smart_default::SmartDefault;
+
+use self::generator::Polylines;
+use crate::game::camera::MainCamera;
+
+/// Whether a [`BoltGroup`]'s points are in world space or relative to the
+/// entity's translation.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum BoltSpace {
+    #[default]
+    World,
+    Local,
+}
+
+/// Bolts that live as long as the entity and get regenerated in place. They
+/// fade with `fade`, from `1.0` down to `0.0`.
+#[derive(Component, Debug, Clone, SmartDefault)]
+pub struct BoltGroup {
+    pub polylines: Polylines,
+    pub space: BoltSpace,
+    #[default(1.0)]
+    pub width: f32,
+    #[default(0.2)]
+    pub taper: f32,
+    #[default(1.0)]
+    pub fade: f32,
+}
+
+impl BoltGroup {
+    pub fn new(space: BoltSpace) -> Self {
+        Self { space, ..default() }
+    }
+}
+
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, Clone, Reflect, SmartDefault)]
+#[reflect(Resource)]
+pub struct BoltConfig {
+    /// Sized to stay visible from the camera's distance across the level,
+    /// which is hundreds of units wide.
+    #[default(1.0)]
+    pub width: f32,
+    #[default(0.2)]
+    pub taper: f32,
+    #[default(LinearRgba::WHITE)]
+    pub start_color: LinearRgba,
+    #[default(LinearRgba::from(SKY_BLUE))]
+    pub end_color: LinearRgba,
+    /// Colors are multiplied by this so bloom picks them up.
+    #[default(6.0)]
+    pub emissive_strength: f32,
+}
+
+/// The batched mesh all bolts are drawn into.
+#[auto_register_type]
+#[derive(Component, Debug, Copy, Clone, Reflect)]
+#[reflect(Component)]
+struct BoltMesh;
+
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, Clone, Reflect)]
+#[reflect(Resource)]
+struct BoltMeshCache(Handle<Mesh>);
+
+impl FromWorld for BoltMeshCache {
+    fn from_world(world: &mut World) -> Self {
+        let mut meshes = world.resource_mut::<Assets<Mesh>>();
+        Self(meshes.add(empty_ribbon_mesh()))
+    }
+}
+
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, Clone, Reflect)]
+#[reflect(Resource)]
+struct BoltMaterialCache(Handle<StandardMaterial>);
+
+impl FromWorld for BoltMaterialCache {
+    fn from_world(world: &mut World) -> Self {
+        let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
+        Self(materials.add(StandardMaterial {
+            base_color: Color::WHITE,
+            unlit: true,
+            alpha_mode: AlphaMode::Add,
+            cull_mode: None,
+            fog_enabled: false,
+            ..Default::default()
+        }))
+    }
+}
+
+#[auto_plugin(app=app)]
+pub(crate) fn plugin(app: &mut App) {
+    app.add_systems(Startup, spawn_bolt_mesh);
+    app.add_systems(
+        PostUpdate,
+        update_bolt_mesh.after(TransformSystem::TransformPropagate),
+    );
+}
+
+fn spawn_bolt_mesh(
+    mut commands: Commands,
+    mesh_cache: Res<BoltMeshCache>,
+    material_cache: Res<BoltMaterialCache>,
+) {
+    commands.spawn((
+        Name::new("BoltMesh"),
+        BoltMesh,
+        Mesh3d(mesh_cache.0.clone()),
+        MeshMaterial3d(material_cache.0.clone()),
+        Transform::default(),
+        Visibility::Hidden,
+        // The mesh moves every frame so its bounds are never up to date
+        NoFrustumCulling,
+    ));
+}
+
+fn update_bolt_mesh(
+    cfg: Res<BoltConfig>,
+    mesh_cache: Res<BoltMeshCache>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut bolt_mesh: Single<&mut Visibility, With<BoltMesh>>,
+    camera: Option<Single<&GlobalTransform, With<MainCamera>>>,
+    groups: Query<(&BoltGroup, &GlobalTransform)>,
+) {
+    let Some(camera) = camera else {
+        return;
+    };
+    if groups.iter().all(|(group, _)| group.polylines.is_empty()) {
+        bolt_mesh.set_if_neq(Visibility::Hidden);
+        return;
+    }
+    let Some(mesh) = meshes.get_mut(&mesh_cache.0) else {
+        return;
+    };
+    let view_position = camera.translation();
+    let mut ribbons = RibbonBuffers::take(mesh);
+    for (group, gt) in groups.iter() {
+        let ribbon = Ribbon {
+            width: group.width,
+            taper: group.taper,
+            fade: group.fade,
+            offset: match group.space {
+                BoltSpace::World => Vec3::ZERO,
+                BoltSpace::Local => gt.translation(),
+            },
+        };
+        for points in group.polylines.iter() {
+            ribbons.push(points, &ribbon, view_position, &cfg);
+        }
+    }
+    ribbons.put(mesh);
+    bolt_mesh.set_if_neq(Visibility::Visible);
+}
+
+struct Ribbon {
+    width: f32,
+    taper: f32,
+    fade: f32,
+    /// Added to every point.
+    offset: Vec3,
+}
+
+/// Vertex buffers of the bolt mesh, taken out of it while it's rebuilt so their
+/// allocations get reused from frame to frame.
+#[derive(Default)]
+struct RibbonBuffers {
+    positions: Vec<[f32; 3]>,
+    normals: Vec<[f32; 3]>,
+    uvs: Vec<[f32; 2]>,
+    colors: Vec<[f32; 4]>,
+    indices: Vec<u32>,
+}
+
+impl RibbonBuffers {
+    fn take(mesh: &mut Mesh) -> Self {
+        let mut buffers = Self::default();
+        if let Some(VertexAttributeValues::Float32x3(positions)) =
+            mesh.remove_attribute(Mesh::ATTRIBUTE_POSITION)
+        {
+            buffers.positions = positions;
+        }
+        if let Some(VertexAttributeValues::Float32x3(normals)) =
+            mesh.remove_attribute(Mesh::ATTRIBUTE_NORMAL)
+        {
+            buffers.normals = normals;
+        }
+        if let Some(VertexAttributeValues::Float32x2(uvs)) =
+            mesh.remove_attribute(Mesh::ATTRIBUTE_UV_0)
+        {
+            buffers.uvs = uvs;
+        }
+        if let Some(VertexAttributeValues::Float32x4(colors)) =
+            mesh.remove_attribute(Mesh::ATTRIBUTE_COLOR)
+        {
+            buffers.colors = colors;
+        }
+        if let Some(Indices::U32(indices)) = mesh.remove_indices() {
+            buffers.indices = indices;
+        }
+        buffers.positions.clear();
+        buffers.normals.clear();
+        buffers.uvs.clear();
+        buffers.colors.clear();
+        buffers.indices.clear();
+        buffers
+    }
+
+    fn put(self, mesh: &mut Mesh) {
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, self.positions);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, self.normals);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, self.uvs);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, self.colors);
+        mesh.insert_indices(Indices::U32(self.indices));
+    }
+
+    /// Adds a strip of quads along `points`, turned to face `view_position`.
+    fn push(&mut self, points: &[Vec3], ribbon: &Ribbon, view_position: Vec3, cfg: &BoltConfig) {
+        if points.len() < 2 {
+            return;
+        }
+        let last = (points.len() - 1) as f32;
+        for (i, &point) in points.iter().enumerate() {
+            let t = i as f32 / last;
+            let point = point + ribbon.offset;
+            // Average the neighbouring segments so joints don't pinch
+            let prev = points[i.saturating_sub(1)];
+            let next = points[(i + 1).min(points.len() - 1)];
+            let along = (next - prev).normalize_or_zero();
+            let to_view = (view_position - point).normalize_or(Vec3::Y);
+            let side = along.cross(to_view).normalize_or_zero();
+            let half_width = ribbon.width * (1.0 - t * (1.0 - ribbon.taper)) * 0.5;
+
+            let color = cfg.start_color.mix(&cfg.end_color, t) * cfg.emissive_strength;
+            let color = color.with_alpha(ribbon.fade);
+            let base = self.positions.len() as u32;
+            for (offset, v) in [(-half_width, 0.0), (half_width, 1.0)] {
+                self.positions.push((point + side * offset).to_array());
+                self.normals.push(to_view.to_array());
+                self.uvs.push([t, v]);
+                self.colors.push(color.to_f32_array());
+            }
+            if i > 0 {
+                self.indices
+                    .extend([base - 2, base - 1, base, base, base - 1, base + 1]);
+            }
+        }
+    }
+}
+
+fn empty_ribbon_mesh() -> Mesh {
+    let mut mesh = Mesh::new(
+        PrimitiveTopology::TriangleList,
+        // Kept in the main world too, it's rebuilt in place every frame
+        RenderAssetUsages::default(),
+    );
+    RibbonBuffers::default().put(&mut mesh);
+    mesh
+}
@@ -0,0 +1,190 @@
+//! Flickering lights. A [`LightFlicker`] modulates the intensity and colour of
+//! its [`PointLight`] around whatever else last set them, picking a new flicker
+//! on every [`FlickerLight`] and brightening for a moment on every
+//! [`FlashLight`]. All flickering lights together are kept under
+//! [`LightFlickerConfig::max_total_intensity`], and everything is toned down
+//! with [`Accessibility::reduced_flashing`].
+
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+use rand::Rng;
+use smart_default::SmartDefault;
+
+use crate::game::rng::global::GlobalRng;
+use crate::game::settings::Accessibility;
+
+#[auto_register_type]
+#[derive(Component, Debug, Clone, Reflect, SmartDefault)]
+#[reflect(Component)]
+#[require(PointLight)]
+#[require(LightFlickerState)]
+pub struct LightFlicker {
+    /// Each flicker scales the intensity by up to this fraction either way.
+    #[default(0.35)]
+    pub intensity_jitter: f32,
+    /// Each flicker tints the colour up to this far towards `tint`.
+    #[default(0.3)]
+    pub color_jitter: f32,
+    #[default(Color::WHITE)]
+    pub tint: Color,
+    /// Extra intensity at the start of a full strength flash, as a multiple of
+    /// the base intensity.
+    #[default(1.5)]
+    pub flash_strength: f32,
+    #[default(0.12)]
+    pub flash_secs: f32,
+}
+
+#[auto_register_type]
+#[derive(Component, Debug, Clone, Reflect, SmartDefault)]
+#[reflect(Component)]
+pub struct LightFlickerState {
+    /// What the light was set to by anything other than the flicker.
+    base_intensity: f32,
+    base_color: Color,
+    /// What the flicker last set, so changes made by anything else stand out.
+    applied_intensity: f32,
+    applied_color: Color,
+    #[default(1.0)]
+    target_level: f32,
+    target_tint: f32,
+    #[default(1.0)]
+    level: f32,
+    tint: f32,
+    /// Strength of the current flash, dying down to `0.0`.
+    flash: f32,
+}
+
+/// Picks a new flicker for `0`, such as when its bolts are regenerated.
+#[auto_register_type]
+#[auto_add_event]
+#[derive(Event, Debug, Copy, Clone, Reflect)]
+pub struct FlickerLight(pub Entity);
+
+/// Briefly brightens `light`, `strength` going from `0.0` to `1.0`.
+#[auto_register_type]
+#[auto_add_event]
+#[derive(Event, Debug, Copy, Clone, Reflect)]
+pub struct FlashLight {
+    pub light: Entity,
+    pub strength: f32,
+}
+
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, Clone, Reflect, SmartDefault)]
+#[reflect(Resource)]
+pub struct LightFlickerConfig {
+    /// Combined intensity of every flickering light, beyond which they're all
+    /// dimmed to fit.
+    #[default(4e8)]
+    pub max_total_intensity: f32,
+    /// Flicker and flashes are scaled by this with reduced flashing.
+    #[default(0.2)]
+    pub reduced_flashing_scale: f32,
+    /// How quickly the flicker eases towards each pick with reduced flashing,
+    /// per second. Otherwise it jumps straight there.
+    #[default(10.0)]
+    pub reduced_flashing_smoothing: f32,
+}
+
+impl LightFlickerConfig {
+    fn scale(&self, accessibility: &Accessibility) -> f32 {
+        if accessibility.reduced_flashing {
+            self.reduced_flashing_scale
+        } else {
+            1.0
+        }
+    }
+}
+
+#[auto_plugin(app=app)]
+pub(crate) fn plugin(app: &mut App) {
+    // After everything that sets the light's base values during the update
+    app.add_systems(
+        PostUpdate,
+        (flicker_lights, flash_lights, apply_flicker).chain(),
+    );
+}
+
+fn flicker_lights(
+    mut flickers: EventReader<FlickerLight>,
+    mut rng: GlobalRng,
+    cfg: Res<LightFlickerConfig>,
+    accessibility: Res<Accessibility>,
+    mut lights: Query<(&LightFlicker, &mut LightFlickerState)>,
+) {
+    let scale = cfg.scale(&accessibility);
+    for &FlickerLight(light) in flickers.read() {
+        let Ok((flicker, mut state)) = lights.get_mut(light) else {
+            continue;
+        };
+        let jitter = (flicker.intensity_jitter * scale).clamp(0.0, 1.0);
+        state.target_level = 1.0 + rng.rng().random_range(-jitter..=jitter);
+        let tint = (flicker.color_jitter * scale).clamp(0.0, 1.0);
+        state.target_tint = rng.rng().random_range(0.0..=tint);
+    }
+}
+
+fn flash_lights(
+    mut flashes: EventReader<FlashLight>,
+    cfg: Res<LightFlickerConfig>,
+    accessibility: Res<Accessibility>,
+    mut lights: Query<&mut LightFlickerState, With<LightFlicker>>,
+) {
+    let scale = cfg.scale(&accessibility);
+    for flash in flashes.read() {
+        let Ok(mut state) = lights.get_mut(flash.light) else {
+            continue;
+        };
+        state.flash = state.flash.max(flash.strength.clamp(0.0, 1.0) * scale);
+    }
+}
+
+fn apply_flicker(
+    time: Res<Time>,
+    cfg: Res<LightFlickerConfig>,
+    accessibility: Res<Accessibility>,
+    mut lights: Query<(&LightFlicker, &mut LightFlickerState, &mut PointLight)>,
+) {
+    let dt = time.delta_secs();
+    let ease = if accessibility.reduced_flashing {
+        1.0 - (-cfg.reduced_flashing_smoothing * dt).exp()
+    } else {
+        1.0
+    };
+
+    let mut total_intensity = 0.0;
+    for (flicker, mut state, light) in lights.iter_mut() {
+        if light.intensity != state.applied_intensity {
+            state.base_intensity = light.intensity;
+        }
+        if light.color != state.applied_color {
+            state.base_color = light.color;
+        }
+        state.level = state.level.lerp(state.target_level, ease);
+        state.tint = state.tint.lerp(state.target_tint, ease);
+        if flicker.flash_secs > 0.0 {
+            state.flash = (state.flash - dt / flicker.flash_secs).max(0.0);
+        } else {
+            state.flash = 0.0;
+        }
+        let level = state.level + state.flash * flicker.flash_strength;
+        state.applied_intensity = state.base_intensity * level.max(0.0);
+        total_intensity += state.applied_intensity;
+    }
+
+    // Dim every light alike so the brightest still stand out
+    let cap = if total_intensity > cfg.max_total_intensity {
+        cfg.max_total_intensity / total_intensity
+    } else {
+        1.0
+    };
+    for (flicker, mut state, mut light) in lights.iter_mut() {
+        state.applied_intensity *= cap;
+        let tint = (state.tint + state.flash).min(1.0);
+        state.applied_color = state.base_color.mix(&flicker.tint, tint);
+        light.intensity = state.applied_intensity;
+        light.color = state.applied_color;
+    }
+}
@@ -1,7 +1,17 @@
 // TODO: split bolt into component marker with behavior and effects
 
+use crate::game::despawn::DespawnAfter;
+use crate::game::effects::bolt::generator::{
+    Branching, Displacement, SphereCrawl, branching_bolt_into, hemisphere_point, sphere_crawl_into,
+};
+use crate::game::effects::bolt::{BoltConfig, BoltGroup, BoltSpace};
+use crate::game::effects::flicker::{FlashLight, FlickerLight, LightFlicker};
+use crate::game::effects::particles::BoltImpact;
+use crate::game::health::{AdjustHp, Dead, Health, MaxHealth};
+use crate::game::pause_controller::PausableSystems;
+use crate::game::physics::layers::GameLayer;
+use crate::game::prefabs::enemy::Enemy;
 use crate::game::rng::global::GlobalRng;
-use crate::game::rng::sphere::RandomSpherePoint;
 use avian3d::prelude::{
     Collider, CollidingEntities, Collisions, Position, RigidBody, Rotation, Sensor,
 };
@@ -9,7 +19,6 @@ use bevy::color::palettes::css::SKY_BLUE;
 use bevy::ecs::query::QueryData;
 use bevy::prelude::*;
 use bevy_auto_plugin::auto_plugin::*;
-use itertools::Itertools;
 use rand::Rng;
 use smart_default::SmartDefault;
 use std::f32::consts::TAU;
@@ -20,9 +29,16 @@ use std::ops::RangeInclusive;
 #[derive(Component, Debug, Default, Copy, Clone, Reflect)]
 #[reflect(Component)]
 #[require(PointLight)]
+#[require(LightFlicker)]
 #[require(Transform)]
 #[require(LightningBallConfig)]
+#[require(LightningBallCharge)]
+#[require(LightningBallChargeMapping)]
+#[require(LightningBallDischarge)]
 #[require(LightningBallSources)]
+#[require(LightningBallArcs)]
+#[require(LightningBallBoltRefresh)]
+#[require(BoltGroup = BoltGroup::new(BoltSpace::Local))]
 pub struct LightningBall;
 
 #[auto_register_type]
@@ -39,6 +55,18 @@ pub struct LightningBallConfig {
     pub spark_segment_len_perc: f32,
     #[default(DEFAULT_LIGHTNING_BALL_SPARK_SEGMENT_MAX_ANGLE_DEG)]
     pub spark_segment_max_angle_deg: f32,
+    #[default(DEFAULT_LIGHTNING_BALL_ARC_GENERATIONS)]
+    pub arc_generations: u32,
+    #[default(DEFAULT_LIGHTNING_BALL_ARC_ROUGHNESS)]
+    pub arc_roughness: f32,
+    #[default(DEFAULT_LIGHTNING_BALL_ARC_BRANCH_DEPTH)]
+    pub arc_branch_depth: u32,
+    #[default(DEFAULT_LIGHTNING_BALL_ARC_BRANCH_PROBABILITY)]
+    pub arc_branch_probability: f32,
+    /// How many times a second the bolts get regenerated, every frame when
+    /// `0.0`.
+    #[default(DEFAULT_LIGHTNING_BALL_BOLT_REFRESH_HZ)]
+    pub bolt_refresh_hz: f32,
 }
 
 pub const DEFAULT_LIGHTNING_BALL_RADIUS: f32 = 0.5;
@@ -48,6 +76,93 @@ pub const DEFAULT_LIGHTNING_BALL_SPARK_COUNT: usize = 10;
 pub const DEFAULT_LIGHTNING_BALL_SPARK_SEGMENT_COUNT: usize = 3;
 pub const DEFAULT_LIGHTNING_BALL_SPARK_SEGMENT_LEN_PERC: f32 = 0.25;
 pub const DEFAULT_LIGHTNING_BALL_SPARK_SEGMENT_MAX_ANGLE_DEG: f32 = 45.0;
+pub const DEFAULT_LIGHTNING_BALL_ARC_GENERATIONS: u32 = 3;
+pub const DEFAULT_LIGHTNING_BALL_ARC_ROUGHNESS: f32 = 0.15;
+pub const DEFAULT_LIGHTNING_BALL_ARC_BRANCH_DEPTH: u32 = 1;
+pub const DEFAULT_LIGHTNING_BALL_ARC_BRANCH_PROBABILITY: f32 = 0.15;
+pub const DEFAULT_LIGHTNING_BALL_BOLT_REFRESH_HZ: f32 = 20.0;
+
+/// Time since the ball's bolts were last regenerated, and whether they're due
+/// this frame according to [`LightningBallConfig::bolt_refresh_hz`].
+#[auto_register_type]
+#[derive(Component, Debug, Copy, Clone, PartialEq, Reflect, SmartDefault)]
+#[reflect(Component)]
+pub struct LightningBallBoltRefresh {
+    pub since_secs: f32,
+    #[default(true)]
+    pub due: bool,
+}
+
+/// Powers a [`LightningBall`] from the [`Health`] of another entity, such as a
+/// spark. Without one the ball runs off its own [`Health`], or burns out over
+/// its [`DespawnAfter`], and otherwise stays fully charged.
+#[auto_register_type]
+#[derive(Component, Debug, Copy, Clone, Reflect)]
+#[reflect(Component)]
+pub struct ChargedBy(pub Entity);
+
+/// How charged the ball is and how lit up that makes it look, both from `0.0`
+/// to `1.0`.
+#[auto_register_type]
+#[derive(Component, Debug, Copy, Clone, PartialEq, Reflect, SmartDefault)]
+#[reflect(Component)]
+pub struct LightningBallCharge {
+    #[default(1.0)]
+    pub charge: f32,
+    #[default(1.0)]
+    pub level: f32,
+}
+
+/// How a ball's [`LightningBallCharge`] drives its size, sparks and glow. The
+/// `curve` maps charge to level, which then picks a value out of each range.
+#[auto_register_type]
+#[derive(Component, Debug, Clone, Reflect, SmartDefault)]
+#[reflect(Component)]
+pub struct LightningBallChargeMapping {
+    #[default(EaseFunction::QuadraticOut)]
+    pub curve: EaseFunction,
+    #[default(0.5..=1.5)]
+    pub scale: RangeInclusive<f32>,
+    #[default(2..=20)]
+    pub spark_count: RangeInclusive<usize>,
+    #[default(2..=5)]
+    pub spark_segment_count: RangeInclusive<usize>,
+    #[default(1e6..=2e8)]
+    pub light_intensity: RangeInclusive<f32>,
+    /// Below this charge the ball sputters, cutting out more the emptier it is.
+    #[default(0.2)]
+    pub sputter_below: f32,
+}
+
+/// Every `interval` the ball arcs into up to `arcs` of the closest conduits and
+/// enemies inside its sensor, dealing `damage_per_arc` to each.
+#[auto_register_type]
+#[derive(Component, Debug, SmartDefault, Clone, Reflect)]
+#[reflect(Component)]
+pub struct LightningBallDischarge {
+    #[default(DEFAULT_LIGHTNING_BALL_ARCS)]
+    pub arcs: usize,
+    #[default(DEFAULT_LIGHTNING_BALL_DAMAGE_PER_ARC)]
+    pub damage_per_arc: f32,
+    #[default(DEFAULT_LIGHTNING_BALL_SENSOR_RADIUS)]
+    pub sensor_radius: f32,
+    #[default(Timer::from_seconds(
+        DEFAULT_LIGHTNING_BALL_DISCHARGE_INTERVAL_SECS,
+        TimerMode::Repeating
+    ))]
+    pub interval: Timer,
+}
+
+pub const DEFAULT_LIGHTNING_BALL_ARCS: usize = 3;
+pub const DEFAULT_LIGHTNING_BALL_DAMAGE_PER_ARC: f32 = 5.0;
+pub const DEFAULT_LIGHTNING_BALL_SENSOR_RADIUS: f32 = 25.0;
+pub const DEFAULT_LIGHTNING_BALL_DISCHARGE_INTERVAL_SECS: f32 = 0.5;
+
+/// Colliders the ball is currently arcing into, closest first.
+#[auto_register_type]
+#[derive(Component, Debug, Default, Clone, Reflect)]
+#[reflect(Component)]
+pub struct LightningBallArcs(pub Vec<Entity>);
 
 #[auto_register_type]
 #[derive(Component, Debug, Default, Clone, Reflect)]
@@ -61,6 +176,7 @@ struct LightningBallSources(Vec<Entity>);
 #[derive(Component, Debug, Copy, Clone, Reflect)]
 #[reflect(Component)]
 #[require(Transform)]
+#[require(BoltGroup = BoltGroup::new(BoltSpace::World))]
 #[relationship(relationship_target = LightningBallSources)]
 struct LightningBallSource(Entity);
 
@@ -71,6 +187,9 @@ struct LightningBallSource(Entity);
 #[require(Collider)]
 pub struct LightningBallConduit;
 
+/// Anything a [`LightningBall`] arcs into.
+type ArcTarget = (Or<(With<LightningBallConduit>, With<Enemy>)>, Without<Dead>);
+
 #[auto_register_type]
 #[auto_init_resource]
 #[derive(Resource, Debug, Clone, Reflect)]
@@ -105,8 +224,14 @@ fn on_lightning_ball_added(
     mut commands: Commands,
     material_cache: Res<LightningBallMeshMaterialCache>,
     mesh_cache: Res<LightningBallMeshCache>,
+    discharges: Query<&LightningBallDischarge>,
 ) {
     let entity = trigger.target();
+    let sensor_radius = discharges
+        .get(entity)
+        .map_or(DEFAULT_LIGHTNING_BALL_SENSOR_RADIUS, |discharge| {
+            discharge.sensor_radius
+        });
     commands.entity(entity).insert((
         Mesh3d(mesh_cache.0.clone()),
         MeshMaterial3d(material_cache.0.clone()),
@@ -123,7 +248,8 @@ fn on_lightning_ball_added(
         children![(
             LightningBallSource(entity),
             Sensor,
-            Collider::sphere(DEFAULT_LIGHTNING_BALL_RADIUS * 50.0),
+            Collider::sphere(sensor_radius),
+            GameLayer::sensor(),
             CollidingEntities::default(),
         )],
     ));
@@ -139,6 +265,10 @@ pub struct LightningBallQueryData {
     pub point_light: Mut<'static, PointLight>,
     pub lightning_ball_config: Mut<'static, LightningBallConfig>,
     lighting_ball_sources: Ref<'static, LightningBallSources>,
+    pub lightning_ball_arcs: Ref<'static, LightningBallArcs>,
+    pub lightning_ball_charge: Ref<'static, LightningBallCharge>,
+    pub bolt_refresh: Ref<'static, LightningBallBoltRefresh>,
+    pub bolts: Mut<'static, BoltGroup>,
 }
 
 #[derive(QueryData)]
@@ -149,214 +279,426 @@ pub struct LightningBallSourceQueryData {
     pub transform: Mut<'static, Transform>,
     pub global_transform: Ref<'static, GlobalTransform>,
     pub colliding_entities: Ref<'static, CollidingEntities>,
+    pub bolts: Mut<'static, BoltGroup>,
+}
+
+fn tick_bolt_refresh(
+    time: Res<Time>,
+    mut balls: Query<
+        (
+            Entity,
+            &LightningBallConfig,
+            &mut LightningBallBoltRefresh,
+            &mut BoltGroup,
+        ),
+        With<LightningBall>,
+    >,
+    mut flickers: EventWriter<FlickerLight>,
+) {
+    for (ball, config, mut refresh, mut bolts) in balls.iter_mut() {
+        let period = if config.bolt_refresh_hz > 0.0 {
+            config.bolt_refresh_hz.recip()
+        } else {
+            0.0
+        };
+        refresh.since_secs += time.delta_secs();
+        refresh.due = refresh.since_secs >= period;
+        if refresh.due {
+            refresh.since_secs = 0.0;
+            flickers.write(FlickerLight(ball));
+        }
+        // Dim a little until the next refresh so cached bolts still flicker
+        let fade = if period > 0.0 {
+            1.0 - 0.5 * (refresh.since_secs / period).min(1.0)
+        } else {
+            1.0
+        };
+        bolts.fade = fade;
+    }
 }
 
 fn animate(
-    mut gizmos: Gizmos,
     mut rng: GlobalRng,
-    lightning_balls_q: Query<LightningBallQueryData, With<LightningBall>>,
+    bolt_cfg: Res<BoltConfig>,
+    mut lightning_balls_q: Query<LightningBallQueryData, With<LightningBall>>,
 ) {
-    for lb in lightning_balls_q.iter() {
+    for mut lb in lightning_balls_q.iter_mut() {
         // Prevent crash during inspector editing and resulting in empty range
         if lb.lightning_ball_config.spark_radius_range.is_empty() {
             continue;
         }
+        if !lb.bolt_refresh.due {
+            continue;
+        }
         let scale = lb.transform.scale.length();
         let scaled_radius_min = lb.lightning_ball_config.spark_radius_range.start() * scale;
         let scaled_radius_max = lb.lightning_ball_config.spark_radius_range.end() * scale;
-        let scaled_target_radius = (scaled_radius_min + scaled_radius_max) / 2.0;
         let total_spark_segment_length =
             TAU * lb.lightning_ball_config.spark_segment_len_perc * scale;
         let spark_segment_length =
             total_spark_segment_length / lb.lightning_ball_config.spark_segment_count as f32;
-        let center = lb.global_transform.translation();
 
-        for _ in 0..lb.lightning_ball_config.spark_count {
-            // Pick a random starting point on the sphere:
-            let transform_point = (*rng.rng()).random_sphere_point(scaled_target_radius);
-
-            // Build a tangent‐space basis at transform_point:
-            let normal = transform_point.normalize();
-            // Pick any vector not parallel to `normal`:
-            let helper = if normal.x.abs() < 0.9 {
-                Vec3::X
-            } else {
-                Vec3::Y
-            };
-            // Gram–Schmidt to get a unit tangent:
-            let tangent = (helper - normal * normal.dot(helper)).normalize();
-            let bitangent = normal.cross(tangent);
-
-            // Choose a truly random initial direction in that tangent plane:
-            let phi = rng.rng().random_range(0.0f32..TAU);
-            let mut direction = tangent * phi.cos() + bitangent * phi.sin();
-            // Note: `direction` is a unit‐length vector, tangent to the sphere at transform_point.
-
-            // Build the list of points (in *local* sphere coordinates):
-            let mut points: Vec<Vec3> =
-                Vec::with_capacity(lb.lightning_ball_config.spark_segment_count + 1);
-            points.push(transform_point);
-            let mut last = transform_point;
-
-            let max_angle = lb
+        let crawl = SphereCrawl {
+            radius_range: scaled_radius_min..=scaled_radius_max,
+            segments: lb.lightning_ball_config.spark_segment_count,
+            segment_length: spark_segment_length,
+            max_angle: lb
                 .lightning_ball_config
                 .spark_segment_max_angle_deg
-                .to_radians();
-
-            for _ in 0..lb.lightning_ball_config.spark_segment_count {
-                // Rotate `direction` by a small random angle *around* the local normal:
-                // (This “wiggles” the direction within the tangent plane.)
-                let theta = rng.rng().random_range(-max_angle..=max_angle);
-                let rot = Quat::from_axis_angle(normal, theta);
-                direction = (rot * direction).normalize();
-
-                // Take a small step “forward” along that tangent:
-                let raw_offset = direction * spark_segment_length;
-                let raw_next = last + raw_offset;
-
-                // Pick a random radius in [min..max] for this new point:
-                let rand_radius = rng
-                    .rng()
-                    .random_range(scaled_radius_min..=scaled_radius_max);
-
-                // Project raw_next onto the sphere of that random radius:
-                let next_on_sphere = raw_next.normalize() * rand_radius;
-                points.push(next_on_sphere);
-                last = next_on_sphere;
-            }
-
-            // Translate each point into world space and draw the polyline:
-            let world_pts: Vec<Vec3> = points
-                .into_iter()
-                .map(|p| Transform::from_translation(center).transform_point(p))
-                .collect();
+                .to_radians(),
+        };
 
-            for (&a, &b) in world_pts.iter().tuple_windows() {
-                gizmos.line_gradient(a, b, Color::WHITE, Color::from(SKY_BLUE));
-            }
+        let bolts = &mut *lb.bolts;
+        bolts.width = bolt_cfg.width * scale;
+        bolts.taper = bolt_cfg.taper;
+        bolts.polylines.clear();
+        for _ in 0..lb.lightning_ball_config.spark_count {
+            sphere_crawl_into(rng.rng(), &crawl, bolts.polylines.push());
         }
     }
 }
 
 fn animate_in_range(
-    mut gizmos: Gizmos,
     mut rng: GlobalRng,
-    lightning_balls_q: Query<
+    bolt_cfg: Res<BoltConfig>,
+    mut lightning_balls_q: Query<
         LightningBallQueryData,
         (With<LightningBall>, Without<LightningBallSource>),
     >,
-    lightning_balls_source_q: Query<
+    mut lightning_balls_source_q: Query<
         LightningBallSourceQueryData,
         (With<LightningBallSource>, Without<LightningBall>),
     >,
-    colliding_q: Query<(&Position, &Rotation), With<LightningBallConduit>>,
+    colliding_q: Query<(&Position, &Rotation), ArcTarget>,
     collisions: Collisions,
+    mut impact_events: EventWriter<BoltImpact>,
 ) {
-    for lb in lightning_balls_q.iter() {
+    for lb in lightning_balls_q.iter_mut() {
         // Prevent crash during inspector editing and resulting in empty range
         if lb.lightning_ball_config.spark_radius_range.is_empty() {
             continue;
         }
+        // Retarget straight away rather than leave bolts pointing at old arcs
+        let regenerate = lb.bolt_refresh.due || lb.lightning_ball_arcs.is_changed();
         let scale = lb.transform.scale.length();
         let scaled_radius_max = lb.lightning_ball_config.spark_radius_range.end() * scale;
         let center = lb.global_transform.translation();
+        let displacement = Displacement {
+            generations: lb.lightning_ball_config.arc_generations,
+            roughness: lb.lightning_ball_config.arc_roughness,
+        };
+        let branching = Branching {
+            max_depth: lb.lightning_ball_config.arc_branch_depth,
+            probability: lb.lightning_ball_config.arc_branch_probability,
+            length: 0.3,
+            max_angle: lb
+                .lightning_ball_config
+                .spark_segment_max_angle_deg
+                .to_radians(),
+        };
 
         for lightning_ball_source_entity in lb.lighting_ball_sources.iter() {
-            let Ok(lb_source) = lightning_balls_source_q.get(lightning_ball_source_entity) else {
+            let Ok(mut lb_source) = lightning_balls_source_q.get_mut(lightning_ball_source_entity)
+            else {
                 continue;
             };
-            for _ in 0..lb.lightning_ball_config.spark_count {
-                for &colliding_entity in lb_source.colliding_entities.iter() {
-                    let Ok((position, rotation)) = colliding_q.get(colliding_entity) else {
-                        continue;
-                    };
-
-                    let Some(cp) = collisions.get(lb_source.entity, colliding_entity) else {
-                        continue;
-                    };
-
-                    let Some(contact) = cp.find_deepest_contact() else {
-                        continue;
-                    };
-
-                    let target_world_pos = if cp.collider1 == colliding_entity {
-                        contact.global_point1(position, rotation)
-                    } else if cp.collider2 == colliding_entity {
-                        contact.global_point2(position, rotation)
-                    } else {
-                        unreachable!("bad collision");
-                    };
-
-                    // Direction + distance from ball center → target
-                    let to_target = target_world_pos - center;
-                    if to_target.length_squared() < f32::EPSILON {
-                        continue;
-                    }
-                    let n = to_target.normalize(); // “pole” for our hemisphere
-                    let full_distance = to_target.length();
-
-                    // Sphere’s outer radius (so we launch exactly from the curved face):
-                    let sphere_radius: f32 = scaled_radius_max;
-
-                    // Build an orthonormal basis {perp1, perp2} ⟂ n:
-                    let helper = if n.x.abs() < 0.9 { Vec3::X } else { Vec3::Y };
-                    let perp1 = (helper - n * n.dot(helper)).normalize();
-                    let perp2 = n.cross(perp1);
-
-                    // Sample a random direction on the hemisphere whose “pole” is n:
-                    let phi = rng.rng().random_range(0.0f32..TAU);
-                    let cos_theta = rng.rng().random_range(0.0f32..=1.0f32);
-                    let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
-                    let hemisphere_dir = perp1 * (sin_theta * phi.cos())
-                        + perp2 * (sin_theta * phi.sin())
-                        + n * (cos_theta);
-
-                    // Compute the random start point on that hemisphere of the sphere:
-                    let start_point: Vec3 = center + hemisphere_dir * sphere_radius;
-
-                    // TODO: space out bends to make it more organic
-                    // Build a jagged bolt from start_point → target_world_pos:
-                    //    interpolate from “start_point on the sphere surface” to the exact target,
-                    //    adding a small sideways jitter at each interior segment.
-                    let mut bolt_points: Vec<Vec3> =
-                        Vec::with_capacity(lb.lightning_ball_config.spark_segment_count + 2);
-                    bolt_points.push(start_point);
-
-                    // Precompute straight vs lateral:
-                    let segments = lb.lightning_ball_config.spark_segment_count as f32;
-                    for i in 1..=lb.lightning_ball_config.spark_segment_count {
-                        let t = (i as f32) / (segments + 1.0);
-                        // Interpolate the distance from start_radius → full_distance:
-                        let distance_along_line =
-                            sphere_radius + (full_distance - sphere_radius) * t;
-                        let ideal_point = center + n * distance_along_line;
-
-                        // Sideways jitter: zero at t=0, zero at t=1, peaks at t=0.5
-                        let max_offset = full_distance * 0.05 * (1.0 - (2.0 * (t - 0.5)).abs());
-                        let mag = rng.rng().random_range(-max_offset..=max_offset);
-                        let angle = rng.rng().random_range(0.0f32..TAU);
-                        let sideways_offset =
-                            perp1 * (angle.cos() * mag) + perp2 * (angle.sin() * mag);
-
-                        let next_pt = ideal_point + sideways_offset;
-                        bolt_points.push(next_pt);
-                    }
-
-                    // End at the collider’s transform:
-                    bolt_points.push(target_world_pos);
-
-                    // Draw
-                    for (&a, &b) in bolt_points.iter().tuple_windows() {
-                        gizmos.line_gradient(a, b, Color::WHITE, Color::from(SKY_BLUE));
-                    }
+            if regenerate {
+                lb_source.bolts.width = bolt_cfg.width;
+                lb_source.bolts.taper = bolt_cfg.taper;
+                lb_source.bolts.polylines.clear();
+            }
+            lb_source.bolts.fade = lb.bolts.fade;
+            for &colliding_entity in lb.lightning_ball_arcs.0.iter() {
+                if !lb_source.colliding_entities.contains(&colliding_entity) {
+                    continue;
                 }
+                let Ok((position, rotation)) = colliding_q.get(colliding_entity) else {
+                    continue;
+                };
+
+                let Some(cp) = collisions.get(lb_source.entity, colliding_entity) else {
+                    continue;
+                };
+
+                let Some(contact) = cp.find_deepest_contact() else {
+                    continue;
+                };
+
+                let target_world_pos = if cp.collider1 == colliding_entity {
+                    contact.global_point1(position, rotation)
+                } else if cp.collider2 == colliding_entity {
+                    contact.global_point2(position, rotation)
+                } else {
+                    unreachable!("bad collision");
+                };
+
+                // Launch from the side of the ball facing the target
+                let Some(pole) = (target_world_pos - center).try_normalize() else {
+                    continue;
+                };
+                impact_events.write(BoltImpact {
+                    target: colliding_entity,
+                    position: target_world_pos,
+                    intensity: lb.lightning_ball_charge.level,
+                });
+
+                if !regenerate {
+                    continue;
+                }
+                for _ in 0..lb.lightning_ball_config.spark_count {
+                    let start_point = center + hemisphere_point(rng.rng(), pole, scaled_radius_max);
+                    branching_bolt_into(
+                        rng.rng(),
+                        start_point,
+                        target_world_pos,
+                        &displacement,
+                        &branching,
+                        &mut lb_source.bolts.polylines,
+                    );
+                }
+            }
+        }
+    }
+}
+
+fn update_charge(
+    mut balls: Query<
+        (
+            &mut LightningBallCharge,
+            &LightningBallChargeMapping,
+            Option<&ChargedBy>,
+            Option<(&Health, &MaxHealth)>,
+            Option<&DespawnAfter>,
+        ),
+        With<LightningBall>,
+    >,
+    sources: Query<(&Health, &MaxHealth)>,
+    mut rng: GlobalRng,
+) {
+    let health_fraction = |(health, max_health): (&Health, &MaxHealth)| {
+        (health.0 / max_health.0.max(f32::EPSILON)).clamp(0.0, 1.0)
+    };
+    for (mut charge, mapping, charged_by, own_health, despawn_after) in balls.iter_mut() {
+        let fraction = match charged_by {
+            Some(charged_by) => sources.get(charged_by.0).map_or(0.0, health_fraction),
+            None => own_health.map(health_fraction).unwrap_or_else(|| {
+                despawn_after.map_or(1.0, |despawn_after| despawn_after.0.fraction_remaining())
+            }),
+        };
+        let mut level = mapping.curve.sample_clamped(fraction);
+        // Cut out at random, more often the closer to empty
+        if fraction < mapping.sputter_below {
+            let emptiness = 1.0 - fraction / mapping.sputter_below;
+            if rng.rng().random_bool(emptiness as f64 * 0.5) {
+                level *= rng.rng().random_range(0.0..0.3);
+            }
+        }
+        charge.set_if_neq(LightningBallCharge {
+            charge: fraction,
+            level,
+        });
+    }
+}
+
+fn apply_charge(
+    mut balls: Query<
+        (
+            &LightningBallCharge,
+            &LightningBallChargeMapping,
+            &LightningBallSources,
+            &mut LightningBallConfig,
+            &mut PointLight,
+            &mut Transform,
+        ),
+        (With<LightningBall>, Changed<LightningBallCharge>),
+    >,
+    mut sensors: Query<&mut Transform, (With<LightningBallSource>, Without<LightningBall>)>,
+) {
+    let lerp = |range: &RangeInclusive<f32>, t: f32| range.start().lerp(*range.end(), t);
+    let lerp_count = |range: &RangeInclusive<usize>, t: f32| {
+        (*range.start() as f32).lerp(*range.end() as f32, t).round() as usize
+    };
+    for (charge, mapping, sources, mut config, mut light, mut transform) in balls.iter_mut() {
+        let level = charge.level;
+        let scale = lerp(&mapping.scale, level).max(f32::EPSILON);
+        transform.scale = Vec3::splat(scale);
+        config.spark_count = lerp_count(&mapping.spark_count, level);
+        config.spark_segment_count = lerp_count(&mapping.spark_segment_count, level).max(1);
+        light.intensity = lerp(&mapping.light_intensity, level);
+        // Keep the sensor the same size whatever the ball's scale
+        let mut iter = sensors.iter_many_mut(sources.iter());
+        while let Some(mut sensor_transform) = iter.fetch_next() {
+            sensor_transform.scale = Vec3::splat(scale.recip());
+        }
+    }
+}
+
+/// Resizes the sensor when [`LightningBallDischarge::sensor_radius`] changes.
+fn sync_sensor_radius(
+    balls: Query<(&LightningBallDischarge, &LightningBallSources), Changed<LightningBallDischarge>>,
+    mut sensors: Query<&mut Collider, With<LightningBallSource>>,
+) {
+    for (discharge, sources) in balls.iter() {
+        let mut iter = sensors.iter_many_mut(sources.iter());
+        while let Some(mut collider) = iter.fetch_next() {
+            let radius = collider.shape().as_ball().map(|ball| ball.radius);
+            if radius != Some(discharge.sensor_radius) {
+                *collider = Collider::sphere(discharge.sensor_radius);
             }
         }
     }
 }
 
+fn select_arcs(
+    mut balls: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &LightningBallDischarge,
+            &LightningBallSources,
+            &mut LightningBallArcs,
+            &LightningBallCharge,
+        ),
+        With<LightningBall>,
+    >,
+    sources: Query<&CollidingEntities, With<LightningBallSource>>,
+    targets: Query<&Position, ArcTarget>,
+    conduits: Query<(), With<LightningBallConduit>>,
+    mut flashes: EventWriter<FlashLight>,
+) {
+    for (ball, gt, discharge, ball_sources, mut arcs, charge) in balls.iter_mut() {
+        let center = gt.translation();
+        let mut in_range = ball_sources
+            .iter()
+            .filter_map(|source| sources.get(source).ok())
+            .flat_map(|colliding| colliding.iter().copied())
+            .filter_map(|target| {
+                let position = targets.get(target).ok()?;
+                Some((target, position.0.distance_squared(center)))
+            })
+            .collect::<Vec<_>>();
+        in_range.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+        let closest = in_range
+            .into_iter()
+            .map(|(target, _)| target)
+            .take(discharge.arcs)
+            .collect::<Vec<_>>();
+        if arcs.0 != closest {
+            // Flash as a bolt first hits a conduit
+            if closest
+                .iter()
+                .any(|target| conduits.contains(*target) && !arcs.0.contains(target))
+            {
+                flashes.write(FlashLight {
+                    light: ball,
+                    strength: charge.level,
+                });
+            }
+            arcs.0 = closest;
+        }
+    }
+}
+
+fn discharge(
+    time: Res<Time>,
+    mut balls: Query<(Entity, &mut LightningBallDischarge, &LightningBallArcs)>,
+    parents: Query<&ChildOf>,
+    damageable: Query<(), (With<Health>, Without<Dead>)>,
+    mut adjust_hp_events: EventWriter<AdjustHp>,
+) {
+    for (ball, mut discharge, arcs) in balls.iter_mut() {
+        if !discharge.interval.tick(time.delta()).just_finished() {
+            continue;
+        }
+        let damage = discharge.damage_per_arc;
+        // Colliders can belong to a child of whatever has the health
+        adjust_hp_events.write_batch(
+            arcs.0
+                .iter()
+                .filter_map(|&target| {
+                    std::iter::once(target)
+                        .chain(parents.iter_ancestors(target))
+                        .find(|&entity| damageable.contains(entity))
+                })
+                .map(|target| AdjustHp::new(target, -damage).with_source(ball)),
+        );
+    }
+}
+
 #[auto_plugin(app=app)]
 pub(crate) fn plugin(app: &mut App) {
     app.add_observer(on_lightning_ball_added);
-    app.add_systems(Update, (animate, animate_in_range));
+    app.add_systems(
+        Update,
+        (sync_sensor_radius, select_arcs, discharge)
+            .chain()
+            .in_set(PausableSystems),
+    );
+    app.add_systems(
+        Update,
+        (
+            (update_charge, apply_charge).chain(),
+            tick_bolt_refresh,
+            (animate, animate_in_range.after(select_arcs)),
+        )
+            .chain(),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::health;
+    use crate::game::prefabs::tower::Tower;
+
+    fn test_app() -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(health::plugin);
+        app.add_systems(Update, discharge);
+        app
+    }
+
+    fn discharging_into(app: &mut App, targets: Vec<Entity>) {
+        app.world_mut().spawn((
+            LightningBallDischarge {
+                // Discharges every update
+                interval: Timer::from_seconds(0.0, TimerMode::Repeating),
+                ..default()
+            },
+            LightningBallArcs(targets),
+        ));
+    }
+
+    #[test]
+    fn damages_enemies() {
+        let mut app = test_app();
+        let enemy = app
+            .world_mut()
+            .spawn((Enemy::BaseSkele, Health(100.0)))
+            .id();
+        let collider = app.world_mut().spawn(ChildOf(enemy)).id();
+        discharging_into(&mut app, vec![collider]);
+        app.update();
+        app.update();
+
+        let health = app.world().get::<Health>(enemy).unwrap();
+        assert!(health.0 < 100.0);
+    }
+
+    #[test]
+    fn damages_conduit_owners() {
+        let mut app = test_app();
+        let tower = app.world_mut().spawn((Tower, Health(100.0))).id();
+        let conduit = app
+            .world_mut()
+            .spawn((LightningBallConduit, ChildOf(tower)))
+            .id();
+        discharging_into(&mut app, vec![conduit]);
+        app.update();
+        app.update();
+
+        let health = app.world().get::<Health>(tower).unwrap();
+        assert!(health.0 < 100.0);
+    }
 }
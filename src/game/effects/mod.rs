@@ -1,9 +1,17 @@
 use bevy::prelude::*;
 use bevy_auto_plugin::auto_plugin::*;
 
+pub mod bolt;
+pub mod flicker;
 pub mod lightning_ball;
+pub mod particles;
 
 #[auto_plugin(app=app)]
 pub(crate) fn plugin(app: &mut App) {
-    app.add_plugins(lightning_ball::plugin);
+    app.add_plugins((
+        bolt::plugin,
+        flicker::plugin,
+        lightning_ball::plugin,
+        particles::plugin,
+    ));
 }
@@ -0,0 +1,353 @@
+//! Electric particle effects: a crackle around every [`LightningBall`], impact
+//! sparks where its bolts land, a burst whenever a [`Spark`] jumps and an
+//! ember trail behind sparks. Each effect is scaled by its
+//! [`EffectIntensity`], driven by the charge of whatever the effect belongs to.
+
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+use bevy_hanabi::prelude::{
+    AlphaMode, Attribute, ColorOverLifetimeModifier, CpuValue, EffectAsset, EffectProperties,
+    EffectSpawner, ExprWriter, Gradient, LinearDragModifier, ParticleEffect, SetAttributeModifier,
+    SetPositionSphereModifier, SetVelocitySphereModifier, ShapeDimension, SpawnerSettings,
+};
+
+use crate::game::despawn::DespawnAfter;
+use crate::game::effects::lightning_ball::{LightningBall, LightningBallCharge};
+use crate::game::health::{Health, MaxHealth};
+use crate::game::spark::{Spark, Zapping};
+
+/// Name of the `f32` property every electric effect is scaled by.
+pub const INTENSITY: &str = "intensity";
+
+/// Scales the size, speed and spawn rate of an electric effect, `1.0` being
+/// its normal look.
+#[auto_register_type]
+#[derive(Component, Debug, Copy, Clone, PartialEq, Reflect)]
+#[reflect(Component)]
+#[require(EffectProperties)]
+pub struct EffectIntensity(pub f32);
+
+impl Default for EffectIntensity {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+/// Sent for every target a bolt lands on this frame.
+#[auto_add_event]
+#[derive(Event, Debug, Copy, Clone, Reflect)]
+pub struct BoltImpact {
+    pub target: Entity,
+    pub position: Vec3,
+    pub intensity: f32,
+}
+
+/// Emits sparks where bolts land on `target`, for as long as they keep landing.
+#[auto_register_type]
+#[derive(Component, Debug, Copy, Clone, Reflect)]
+#[reflect(Component)]
+struct ImpactSparks {
+    target: Entity,
+    idle_secs: f32,
+}
+
+impl ImpactSparks {
+    /// Stops emitting once bolts haven't landed for this long.
+    const ACTIVE_SECS: f32 = 0.1;
+    /// Despawned once the last particles are gone.
+    const LINGER_SECS: f32 = 1.0;
+}
+
+#[auto_register_type]
+#[derive(Component, Debug, Copy, Clone, Reflect)]
+#[reflect(Component)]
+struct Crackle;
+
+#[auto_register_type]
+#[derive(Component, Debug, Copy, Clone, Reflect)]
+#[reflect(Component)]
+struct EmberTrail;
+
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct ElectricEffects {
+    pub crackle: Handle<EffectAsset>,
+    pub impact: Handle<EffectAsset>,
+    pub jump_burst: Handle<EffectAsset>,
+    pub ember_trail: Handle<EffectAsset>,
+}
+
+impl FromWorld for ElectricEffects {
+    fn from_world(world: &mut World) -> Self {
+        let mut effects = world.resource_mut::<Assets<EffectAsset>>();
+        Self {
+            crackle: effects.add(electric_sparks(ElectricSparks {
+                name: "Crackle",
+                capacity: 512,
+                spawner: SpawnerSettings::rate(60.0.into()),
+                radius: 1.2,
+                speed: 2.0,
+                lifetime: 0.25,
+                size: 0.08,
+                drag: 4.0,
+            })),
+            impact: effects.add(electric_sparks(ElectricSparks {
+                name: "Impact",
+                capacity: 256,
+                spawner: SpawnerSettings::rate(40.0.into()),
+                radius: 0.5,
+                speed: 12.0,
+                lifetime: 0.3,
+                size: 0.3,
+                drag: 6.0,
+            })),
+            jump_burst: effects.add(electric_sparks(ElectricSparks {
+                name: "JumpBurst",
+                capacity: 128,
+                spawner: SpawnerSettings::once(64.0.into()),
+                radius: 1.0,
+                speed: 25.0,
+                lifetime: 0.4,
+                size: 0.5,
+                drag: 5.0,
+            })),
+            ember_trail: effects.add(electric_sparks(ElectricSparks {
+                name: "EmberTrail",
+                capacity: 512,
+                spawner: SpawnerSettings::rate(80.0.into()),
+                radius: 0.5,
+                speed: 1.0,
+                lifetime: 0.6,
+                size: 0.25,
+                drag: 2.0,
+            })),
+        }
+    }
+}
+
+struct ElectricSparks {
+    name: &'static str,
+    capacity: u32,
+    spawner: SpawnerSettings,
+    radius: f32,
+    speed: f32,
+    lifetime: f32,
+    size: f32,
+    drag: f32,
+}
+
+/// Sparks shooting out of a sphere and fading from white hot to blue. Colors go
+/// well above `1.0` so they bloom.
+fn electric_sparks(sparks: ElectricSparks) -> EffectAsset {
+    let writer = ExprWriter::new();
+    let intensity = writer.add_property(INTENSITY, 1.0.into());
+    let jitter = || writer.lit(0.5).uniform(writer.lit(1.0));
+
+    let init_position = SetPositionSphereModifier {
+        center: writer.lit(Vec3::ZERO).expr(),
+        radius: writer.lit(sparks.radius).expr(),
+        dimension: ShapeDimension::Volume,
+    };
+    let init_velocity = SetVelocitySphereModifier {
+        center: writer.lit(Vec3::ZERO).expr(),
+        speed: (jitter() * writer.lit(sparks.speed) * writer.prop(intensity)).expr(),
+    };
+    let init_lifetime = SetAttributeModifier::new(
+        Attribute::LIFETIME,
+        (jitter() * writer.lit(sparks.lifetime)).expr(),
+    );
+    let init_size = SetAttributeModifier::new(
+        Attribute::SIZE,
+        (writer.lit(sparks.size) * writer.prop(intensity)).expr(),
+    );
+    let drag = LinearDragModifier::new(writer.lit(sparks.drag).expr());
+
+    let mut gradient = Gradient::new();
+    gradient.add_key(0.0, Vec4::new(8.0, 8.0, 8.0, 1.0));
+    gradient.add_key(0.3, Vec4::new(1.0, 3.0, 8.0, 1.0));
+    gradient.add_key(1.0, Vec4::new(0.2, 0.6, 2.0, 0.0));
+
+    EffectAsset::new(sparks.capacity, sparks.spawner, writer.finish())
+        .with_name(sparks.name)
+        .with_alpha_mode(AlphaMode::Add)
+        .init(init_position)
+        .init(init_velocity)
+        .init(init_lifetime)
+        .init(init_size)
+        .update(drag)
+        .render(ColorOverLifetimeModifier::new(gradient))
+}
+
+#[auto_plugin(app=app)]
+pub(crate) fn plugin(app: &mut App) {
+    app.add_observer(add_crackle);
+    app.add_observer(add_ember_trail);
+    app.add_observer(burst_on_jump);
+    app.add_systems(
+        Update,
+        (
+            emit_impacts,
+            charge_crackles,
+            charge_spark_effects,
+            apply_intensity,
+        )
+            .chain(),
+    );
+}
+
+fn add_crackle(
+    trigger: Trigger<OnAdd, LightningBall>,
+    mut commands: Commands,
+    effects: Res<ElectricEffects>,
+) {
+    commands.entity(trigger.target()).with_child((
+        Name::new("Crackle"),
+        Crackle,
+        ParticleEffect::new(effects.crackle.clone()),
+        EffectIntensity::default(),
+    ));
+}
+
+fn add_ember_trail(
+    trigger: Trigger<OnAdd, Spark>,
+    mut commands: Commands,
+    effects: Res<ElectricEffects>,
+) {
+    commands.entity(trigger.target()).with_child((
+        Name::new("EmberTrail"),
+        EmberTrail,
+        ParticleEffect::new(effects.ember_trail.clone()),
+        EffectIntensity::default(),
+    ));
+}
+
+fn burst_on_jump(
+    trigger: Trigger<OnInsert, Zapping>,
+    mut commands: Commands,
+    effects: Res<ElectricEffects>,
+    sparks: Query<(&Zapping, Option<(&Health, &MaxHealth)>)>,
+    targets: Query<&GlobalTransform>,
+) {
+    let Ok((zapping, charge)) = sparks.get(trigger.target()) else {
+        return;
+    };
+    let Ok(target_gt) = targets.get(zapping.0) else {
+        return;
+    };
+    commands.spawn((
+        Name::new("JumpBurst"),
+        ParticleEffect::new(effects.jump_burst.clone()),
+        EffectIntensity(charge_fraction(charge)),
+        Transform::from_translation(target_gt.translation()),
+        DespawnAfter::secs(1.0),
+    ));
+}
+
+fn emit_impacts(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut impacts: EventReader<BoltImpact>,
+    effects: Res<ElectricEffects>,
+    mut emitters: Query<(
+        Entity,
+        &mut ImpactSparks,
+        &mut Transform,
+        &mut EffectIntensity,
+        Option<&mut EffectSpawner>,
+    )>,
+) {
+    for (entity, mut sparks, _, _, spawner) in emitters.iter_mut() {
+        sparks.idle_secs += time.delta_secs();
+        if sparks.idle_secs >= ImpactSparks::LINGER_SECS {
+            commands.entity(entity).despawn();
+        } else if let Some(mut spawner) = spawner {
+            spawner.active = sparks.idle_secs < ImpactSparks::ACTIVE_SECS;
+        }
+    }
+
+    for impact in impacts.read() {
+        // Emitters that just lingered out are already on their way out
+        let emitter = emitters.iter_mut().find(|(_, sparks, ..)| {
+            sparks.target == impact.target && sparks.idle_secs < ImpactSparks::LINGER_SECS
+        });
+        match emitter {
+            Some((_, mut sparks, mut transform, mut intensity, _)) => {
+                sparks.idle_secs = 0.0;
+                transform.translation = impact.position;
+                intensity.0 = impact.intensity;
+            }
+            None => {
+                commands.spawn((
+                    Name::new("ImpactSparks"),
+                    ImpactSparks {
+                        target: impact.target,
+                        idle_secs: 0.0,
+                    },
+                    ParticleEffect::new(effects.impact.clone()),
+                    EffectIntensity(impact.intensity),
+                    Transform::from_translation(impact.position),
+                ));
+            }
+        }
+    }
+}
+
+fn charge_crackles(
+    balls: Query<(&Children, &LightningBallCharge), With<LightningBall>>,
+    mut crackles: Query<&mut EffectIntensity, With<Crackle>>,
+) {
+    for (children, charge) in balls.iter() {
+        let mut iter = crackles.iter_many_mut(children.iter());
+        while let Some(mut intensity) = iter.fetch_next() {
+            intensity.set_if_neq(EffectIntensity(charge.level));
+        }
+    }
+}
+
+fn charge_spark_effects(
+    sparks: Query<(&Children, Option<(&Health, &MaxHealth)>), With<Spark>>,
+    mut trails: Query<&mut EffectIntensity, With<EmberTrail>>,
+) {
+    for (children, charge) in sparks.iter() {
+        let fraction = charge_fraction(charge);
+        let mut iter = trails.iter_many_mut(children.iter());
+        while let Some(mut intensity) = iter.fetch_next() {
+            intensity.set_if_neq(EffectIntensity(fraction));
+        }
+    }
+}
+
+fn apply_intensity(
+    assets: Res<Assets<EffectAsset>>,
+    mut effects: Query<(
+        &EffectIntensity,
+        &ParticleEffect,
+        &mut EffectProperties,
+        Option<&mut EffectSpawner>,
+    )>,
+) {
+    for (intensity, effect, properties, spawner) in effects.iter_mut() {
+        EffectProperties::set_if_changed(properties, INTENSITY, intensity.0.into());
+        // Bursts have already gone off, only continuous effects can be thinned out
+        let (Some(mut spawner), Some(asset)) = (spawner, assets.get(&effect.handle)) else {
+            continue;
+        };
+        if !asset.spawner.is_forever() {
+            continue;
+        }
+        let [min, max] = asset.spawner.count().range();
+        let count = CpuValue::Uniform((min * intensity.0, max * intensity.0));
+        if spawner.settings.count() != count {
+            spawner.settings.set_count(count);
+        }
+    }
+}
+
+/// How charged a spark is, from `0.0` to `1.0`.
+fn charge_fraction(charge: Option<(&Health, &MaxHealth)>) -> f32 {
+    charge.map_or(1.0, |(health, max_health)| {
+        (health.0 / max_health.0.max(f32::EPSILON)).clamp(0.0, 1.0)
+    })
+}
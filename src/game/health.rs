@@ -18,6 +18,16 @@ pub struct MaxHealth(pub f32);
 pub struct AdjustHp {
     pub target: Entity,
     pub amount: f32,
+    /// Whoever caused the change, if anyone.
+    pub source: Option<Entity>,
+}
+
+/// Sent for every [`AdjustHp`] applied to a living target, with how much its
+/// health actually changed. Damage past zero health isn't counted.
+#[derive(Event, Debug)]
+pub struct HpAdjusted {
+    pub target: Entity,
+    pub amount: f32,
 }
 
 #[auto_register_type]
@@ -30,6 +40,7 @@ pub struct Dead;
 pub fn plugin(app: &mut App) {
     app.add_systems(Update, handle_adjust_hp);
     app.add_event::<AdjustHp>();
+    app.add_event::<HpAdjusted>();
 }
 
 // Internals
@@ -37,13 +48,22 @@ pub fn plugin(app: &mut App) {
 fn handle_adjust_hp(
     mut commands: Commands,
     mut damage_reader: EventReader<AdjustHp>,
-    mut health_query: Query<&mut Health, Without<Dead>>,
+    mut adjusted_writer: EventWriter<HpAdjusted>,
+    mut health_query: Query<(&mut Health, Option<&MaxHealth>), Without<Dead>>,
 ) {
-    for AdjustHp { target, amount } in damage_reader.read() {
-        let Ok(mut health) = health_query.get_mut(*target) else {
+    for AdjustHp { target, amount, .. } in damage_reader.read() {
+        let Ok((mut health, max_health)) = health_query.get_mut(*target) else {
             continue;
         };
+        let before = health.0;
         health.0 += amount;
+        if let Some(max_health) = max_health {
+            health.0 = health.0.min(max_health.0);
+        }
+        adjusted_writer.write(HpAdjusted {
+            target: *target,
+            amount: health.0.max(0.0) - before.max(0.0),
+        });
 
         if health.0 <= 0.0 {
             commands.entity(*target).insert(Dead);
@@ -53,6 +73,15 @@ fn handle_adjust_hp(
 
 impl AdjustHp {
     pub fn new(target: Entity, amount: f32) -> Self {
-        Self { target, amount }
+        Self {
+            target,
+            amount,
+            source: None,
+        }
+    }
+
+    pub fn with_source(mut self, source: Entity) -> Self {
+        self.source = Some(source);
+        self
     }
 }
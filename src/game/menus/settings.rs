@@ -2,10 +2,18 @@
 //!
 //! Additional settings and accessibility options should go here.
 
-use bevy::{audio::Volume, input::common_conditions::input_just_pressed, prelude::*, ui::Val::*};
+use bevy::{
+    audio::Volume, ecs::system::IntoObserverSystem, input::common_conditions::input_just_pressed,
+    prelude::*, ui::Val::*,
+};
 use bevy_auto_plugin::auto_plugin::*;
 
-use crate::game::{menus::Menu, screens::Screen, theme::prelude::*};
+use crate::game::{
+    menus::Menu,
+    screens::Screen,
+    settings::{Accessibility, Graphics, MAX_VOLUME},
+    theme::prelude::*,
+};
 
 fn spawn_settings_menu(mut commands: Commands) {
     commands.spawn((
@@ -39,6 +47,42 @@ fn settings_grid() -> impl Bundle {
                 }
             ),
             global_volume_widget(),
+            (
+                widget::label("VSync"),
+                Node {
+                    justify_self: JustifySelf::End,
+                    ..default()
+                }
+            ),
+            toggle_widget("VSync", VsyncLabel, toggle_vsync),
+            (
+                widget::label("Bloom"),
+                Node {
+                    justify_self: JustifySelf::End,
+                    ..default()
+                }
+            ),
+            toggle_widget("Bloom", BloomLabel, toggle_bloom),
+            (
+                widget::label("Reduced Flashing"),
+                Node {
+                    justify_self: JustifySelf::End,
+                    ..default()
+                }
+            ),
+            toggle_widget(
+                "Reduced Flashing",
+                ReducedFlashingLabel,
+                toggle_reduced_flashing
+            ),
+            (
+                widget::label("Camera Shake"),
+                Node {
+                    justify_self: JustifySelf::End,
+                    ..default()
+                }
+            ),
+            camera_shake_widget(),
         ],
     )
 }
@@ -67,7 +111,6 @@ fn global_volume_widget() -> impl Bundle {
 }
 
 const MIN_VOLUME: f32 = 0.0;
-const MAX_VOLUME: f32 = 3.0;
 
 fn lower_global_volume(_: Trigger<Pointer<Click>>, mut global_volume: ResMut<GlobalVolume>) {
     let linear = (global_volume.volume.to_linear() - 0.1).max(MIN_VOLUME);
@@ -92,6 +135,136 @@ fn update_global_volume_label(
     label.0 = format!("{percent:3.0}%");
 }
 
+/// An On/Off label, updated by whatever system looks for `label`, next to a
+/// button that toggles it.
+fn toggle_widget<E, B, M, I>(name: &'static str, label: impl Component, action: I) -> impl Bundle
+where
+    E: Event,
+    B: Bundle,
+    I: IntoObserverSystem<E, B, M>,
+{
+    (
+        Name::new(format!("{name} Widget")),
+        Node {
+            justify_self: JustifySelf::Start,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        children![
+            (
+                Name::new(format!("Current {name}")),
+                Node {
+                    width: Px(60.0),
+                    padding: UiRect::horizontal(Px(10.0)),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                children![(widget::label(""), label)],
+            ),
+            widget::button_medium("Toggle", action),
+        ],
+    )
+}
+
+fn on_off(on: bool) -> String {
+    if on { "On" } else { "Off" }.to_string()
+}
+
+fn toggle_vsync(_: Trigger<Pointer<Click>>, mut graphics: ResMut<Graphics>) {
+    graphics.vsync = !graphics.vsync;
+}
+
+#[auto_register_type]
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct VsyncLabel;
+
+fn update_vsync_label(graphics: Res<Graphics>, mut label: Single<&mut Text, With<VsyncLabel>>) {
+    label.0 = on_off(graphics.vsync);
+}
+
+fn toggle_bloom(_: Trigger<Pointer<Click>>, mut graphics: ResMut<Graphics>) {
+    graphics.bloom = !graphics.bloom;
+}
+
+#[auto_register_type]
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct BloomLabel;
+
+fn update_bloom_label(graphics: Res<Graphics>, mut label: Single<&mut Text, With<BloomLabel>>) {
+    label.0 = on_off(graphics.bloom);
+}
+
+fn toggle_reduced_flashing(_: Trigger<Pointer<Click>>, mut accessibility: ResMut<Accessibility>) {
+    accessibility.reduced_flashing = !accessibility.reduced_flashing;
+}
+
+#[auto_register_type]
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct ReducedFlashingLabel;
+
+fn update_reduced_flashing_label(
+    accessibility: Res<Accessibility>,
+    mut label: Single<&mut Text, With<ReducedFlashingLabel>>,
+) {
+    label.0 = on_off(accessibility.reduced_flashing);
+}
+
+fn camera_shake_widget() -> impl Bundle {
+    (
+        Name::new("Camera Shake Widget"),
+        Node {
+            justify_self: JustifySelf::Start,
+            ..default()
+        },
+        children![
+            widget::button_small("-", lower_camera_shake),
+            (
+                Name::new("Current Camera Shake"),
+                Node {
+                    padding: UiRect::horizontal(Px(10.0)),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                children![(widget::label(""), CameraShakeLabel)],
+            ),
+            widget::button_small("+", raise_camera_shake),
+        ],
+    )
+}
+
+const CAMERA_SHAKE_STEP: f32 = 0.1;
+
+fn lower_camera_shake(_: Trigger<Pointer<Click>>, mut accessibility: ResMut<Accessibility>) {
+    // Counted in whole steps so it lands exactly on zero
+    let steps = (accessibility.camera_shake / CAMERA_SHAKE_STEP).round() - 1.0;
+    accessibility.camera_shake = (steps * CAMERA_SHAKE_STEP).max(0.0);
+}
+
+fn raise_camera_shake(_: Trigger<Pointer<Click>>, mut accessibility: ResMut<Accessibility>) {
+    let steps = (accessibility.camera_shake / CAMERA_SHAKE_STEP).round() + 1.0;
+    accessibility.camera_shake = (steps * CAMERA_SHAKE_STEP).min(1.0);
+}
+
+#[auto_register_type]
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct CameraShakeLabel;
+
+fn update_camera_shake_label(
+    accessibility: Res<Accessibility>,
+    mut label: Single<&mut Text, With<CameraShakeLabel>>,
+) {
+    label.0 = if accessibility.camera_shake <= 0.0 {
+        "Off".to_string()
+    } else {
+        let percent = 100.0 * accessibility.camera_shake;
+        format!("{percent:3.0}%")
+    };
+}
+
 fn go_back_on_click(
     _: Trigger<Pointer<Click>>,
     screen: Res<State<Screen>>,
@@ -122,6 +295,13 @@ pub(super) fn plugin(app: &mut App) {
 
     app.add_systems(
         Update,
-        update_global_volume_label.run_if(in_state(Menu::Settings)),
+        (
+            update_global_volume_label,
+            update_vsync_label,
+            update_bloom_label,
+            update_reduced_flashing_label,
+            update_camera_shake_label,
+        )
+            .run_if(in_state(Menu::Settings)),
     );
 }
@@ -1,11 +1,14 @@
 #[macro_use]
 mod enforce_exists;
 
+mod abilities;
 mod asset_tracking;
 mod audio;
 pub mod behaviors;
+mod build;
 mod camera;
 mod constants;
+mod currency;
 mod despawn;
 #[cfg(feature = "dev")]
 mod dev;
@@ -13,15 +16,20 @@ mod effects;
 mod game_system_set;
 mod health;
 mod menus;
+mod navigation;
 mod pause_controller;
 mod physics;
 mod prefabs;
 mod rng;
+mod run;
 mod scenes;
 pub mod screens;
+mod settings;
 mod snapshot;
 mod spark;
+mod steering;
 mod theme;
+mod waves;
 
 use crate::game::rng::RngPlugin;
 use bevy::app::PluginGroupBuilder;
@@ -62,6 +70,7 @@ impl Plugin for GamePlugin {
         app.add_plugins(pause_controller::plugin);
         app.add_plugins(physics::plugin);
         app.add_plugins(prefabs::plugin);
+        app.add_plugins(navigation::plugin);
         app.add_plugins(behaviors::plugin);
         app.add_plugins(effects::plugin);
         app.add_plugins(scenes::plugin);
@@ -70,7 +79,14 @@ impl Plugin for GamePlugin {
         app.add_plugins(menus::plugin);
         app.add_plugins(screens::plugin);
         app.add_plugins(health::plugin);
+        app.add_plugins(settings::plugin);
         app.add_plugins(spark::plugin);
+        app.add_plugins(steering::plugin);
+        app.add_plugins(waves::plugin);
+        app.add_plugins(run::plugin);
+        app.add_plugins(currency::plugin);
+        app.add_plugins(build::plugin);
+        app.add_plugins(abilities::plugin);
         app.add_plugins(despawn::plugin::<PreUpdate>);
     }
 }
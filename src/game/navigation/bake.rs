@@ -0,0 +1,147 @@
+use std::sync::Arc;
+
+use avian3d::prelude::{
+    Collider, ColliderAabb, CollisionLayers, LayerMask, RigidBody, Sensor, SpatialQuery,
+    SpatialQueryFilter,
+};
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+
+use super::grid::GridData;
+use super::{NavConfig, NavGrid};
+use crate::game::physics::layers::GameLayer;
+use crate::game::screens::Screen;
+
+/// Static collider the grid is baked from. Inserted automatically so that
+/// despawning one can trigger a rebake.
+#[auto_register_type]
+#[derive(Component, Debug, Default, Copy, Clone, Reflect)]
+#[reflect(Component)]
+pub struct NavObstacle;
+
+/// Counts down to the next bake, restarted whenever the level changes.
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, Default, Clone, Reflect)]
+#[reflect(Resource)]
+struct NavBakeTimer(Option<Timer>);
+
+#[auto_plugin(app=app)]
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnExit(Screen::Gameplay), reset_nav_grid);
+    app.add_systems(Update, (track_obstacles, bake_nav_grid).chain());
+}
+
+fn reset_nav_grid(mut grid: ResMut<NavGrid>, mut timer: ResMut<NavBakeTimer>) {
+    grid.data = Arc::default();
+    grid.version += 1;
+    timer.0 = None;
+}
+
+/// Moving bodies, sensors and enemies don't shape the level.
+fn is_static(rigid_body: Option<&RigidBody>, layers: Option<&CollisionLayers>) -> bool {
+    rigid_body.is_none_or(|rigid_body| *rigid_body == RigidBody::Static)
+        && layers.is_none_or(|layers| {
+            layers.memberships & [GameLayer::Enemy, GameLayer::Sensor] == LayerMask::NONE
+        })
+}
+
+fn track_obstacles(
+    mut commands: Commands,
+    changed: Query<
+        (Entity, Option<&RigidBody>, Option<&CollisionLayers>),
+        (Changed<Collider>, Without<Sensor>),
+    >,
+    mut removed: RemovedComponents<NavObstacle>,
+    cfg: Res<NavConfig>,
+    mut timer: ResMut<NavBakeTimer>,
+) {
+    let mut dirty = removed.read().count() > 0;
+    for (entity, rigid_body, layers) in changed.iter() {
+        if !is_static(rigid_body, layers) {
+            continue;
+        }
+        commands.entity(entity).insert(NavObstacle);
+        dirty = true;
+    }
+    if dirty {
+        // Colliders only show up in spatial queries after the next physics
+        // step, so give them a moment before baking.
+        timer.0 = Some(Timer::from_seconds(cfg.rebake_delay_secs, TimerMode::Once));
+    }
+}
+
+fn bake_nav_grid(
+    time: Res<Time>,
+    mut timer: ResMut<NavBakeTimer>,
+    cfg: Res<NavConfig>,
+    spatial_query: SpatialQuery,
+    terrain: Query<(&ColliderAabb, &CollisionLayers)>,
+    obstacles: Query<(Option<&RigidBody>, Option<&CollisionLayers>), Without<Sensor>>,
+    mut grid: ResMut<NavGrid>,
+) {
+    let Some(bake_timer) = timer.0.as_mut() else {
+        return;
+    };
+    if !bake_timer.tick(time.delta()).finished() {
+        return;
+    }
+    timer.0 = None;
+
+    let Some((min, max)) = terrain
+        .iter()
+        .filter(|(_, layers)| layers.memberships.has_all(GameLayer::Terrain))
+        .map(|(aabb, _)| (aabb.min, aabb.max))
+        .reduce(|(min, max), (aabb_min, aabb_max)| (min.min(aabb_min), max.max(aabb_max)))
+    else {
+        return;
+    };
+
+    let size = ((max.xz() - min.xz()) / cfg.cell_size).floor().as_uvec2();
+    let mut data = GridData::new(min.xz(), cfg.cell_size, size);
+    let terrain_filter = SpatialQueryFilter::from_mask(GameLayer::Terrain);
+    let obstacle_filter = SpatialQueryFilter::from_mask([GameLayer::Default, GameLayer::Tower]);
+    let clearance = cfg.agent_height - cfg.step_height;
+    let probe = Collider::cuboid(
+        cfg.cell_size + cfg.agent_radius * 2.0,
+        clearance,
+        cfg.cell_size + cfg.agent_radius * 2.0,
+    );
+
+    for cell in data.cells().collect::<Vec<_>>() {
+        let center = data.cell_center(cell);
+        let ray_origin = Vec3::new(center.x, max.y + 1.0, center.z);
+        let Some(hit) = spatial_query.cast_ray(
+            ray_origin,
+            Dir3::NEG_Y,
+            max.y - min.y + 2.0,
+            true,
+            &terrain_filter,
+        ) else {
+            continue;
+        };
+        let ground = ray_origin.y - hit.distance;
+        let blocked = hit.normal.y < cfg.min_ground_normal_y
+            || spatial_query
+                .shape_intersections(
+                    &probe,
+                    Vec3::new(
+                        center.x,
+                        ground + cfg.step_height + clearance / 2.0,
+                        center.z,
+                    ),
+                    Quat::IDENTITY,
+                    &obstacle_filter,
+                )
+                .into_iter()
+                .any(|entity| {
+                    obstacles
+                        .get(entity)
+                        .is_ok_and(|(rigid_body, layers)| is_static(rigid_body, layers))
+                });
+        data.set(cell, ground, !blocked);
+    }
+
+    grid.data = Arc::new(data);
+    grid.version += 1;
+}
@@ -0,0 +1,60 @@
+use bevy::color::palettes::css::{ORANGE, RED};
+use bevy::input::common_conditions::input_just_pressed;
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+
+use super::NavGrid;
+use super::path::PathFollower;
+
+/// Draws blocked cells and the paths being followed. Toggled with `N`.
+#[derive(Default, Reflect, GizmoConfigGroup)]
+struct NavGizmos;
+
+#[auto_plugin(app=app)]
+pub(super) fn plugin(app: &mut App) {
+    app.init_gizmo_group::<NavGizmos>();
+    app.world_mut()
+        .resource_mut::<GizmoConfigStore>()
+        .config_mut::<NavGizmos>()
+        .0
+        .enabled = false;
+    app.add_systems(
+        Update,
+        toggle_nav_gizmos.run_if(input_just_pressed(KeyCode::KeyN)),
+    );
+    app.add_systems(Update, (draw_blocked_cells, draw_paths));
+}
+
+fn toggle_nav_gizmos(mut gizmos: ResMut<GizmoConfigStore>) {
+    let (config, _) = gizmos.config_mut::<NavGizmos>();
+    config.enabled = !config.enabled;
+}
+
+fn draw_blocked_cells(mut gizmos: Gizmos<NavGizmos>, grid: Res<NavGrid>) {
+    if !gizmos.config.enabled {
+        return;
+    }
+    let grid = &grid.data;
+    for cell in grid.cells().filter(|&cell| !grid.is_walkable(cell)) {
+        gizmos.rect(
+            Isometry3d::new(
+                grid.cell_center(cell) + Vec3::Y * 0.5,
+                Quat::from_rotation_x(std::f32::consts::FRAC_PI_2),
+            ),
+            Vec2::splat(grid.cell_size * 0.9),
+            RED.with_alpha(0.5),
+        );
+    }
+}
+
+fn draw_paths(mut gizmos: Gizmos<NavGizmos>, followers: Query<(&PathFollower, &GlobalTransform)>) {
+    for (follower, gt) in followers.iter() {
+        gizmos.linestrip(
+            std::iter::once(gt.translation()).chain(follower.remaining().iter().copied()),
+            ORANGE,
+        );
+        for &waypoint in follower.remaining() {
+            gizmos.sphere(Isometry3d::from_translation(waypoint), 2.0, ORANGE);
+        }
+    }
+}
@@ -0,0 +1,317 @@
+//! Walkability grid on the XZ plane and A* over it. Nothing in here touches the
+//! ECS so paths can be found off the main thread.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use bevy::prelude::*;
+
+/// Stops a search that can't reach its goal from flooding the whole grid.
+const MAX_EXPANDED_CELLS: usize = 20_000;
+
+#[derive(Debug, Clone, Default, Reflect)]
+pub struct GridData {
+    /// World position of the corner of cell `(0, 0)`.
+    pub origin: Vec2,
+    pub cell_size: f32,
+    pub size: UVec2,
+    /// Ground height at the center of each cell.
+    pub heights: Vec<f32>,
+    pub walkable: Vec<bool>,
+}
+
+impl GridData {
+    pub fn new(origin: Vec2, cell_size: f32, size: UVec2) -> Self {
+        let len = (size.x * size.y) as usize;
+        Self {
+            origin,
+            cell_size,
+            size,
+            heights: vec![0.0; len],
+            walkable: vec![false; len],
+        }
+    }
+
+    fn index(&self, cell: UVec2) -> usize {
+        (cell.y * self.size.x + cell.x) as usize
+    }
+
+    pub fn cells(&self) -> impl Iterator<Item = UVec2> + '_ {
+        (0..self.size.y).flat_map(move |y| (0..self.size.x).map(move |x| UVec2::new(x, y)))
+    }
+
+    pub fn cell_at(&self, position: Vec3) -> Option<UVec2> {
+        let local = (position.xz() - self.origin) / self.cell_size;
+        if local.x < 0.0 || local.y < 0.0 {
+            return None;
+        }
+        let cell = local.floor().as_uvec2();
+        (cell.x < self.size.x && cell.y < self.size.y).then_some(cell)
+    }
+
+    /// Center of `cell`, on the ground.
+    pub fn cell_center(&self, cell: UVec2) -> Vec3 {
+        let xz = self.origin + (cell.as_vec2() + 0.5) * self.cell_size;
+        Vec3::new(xz.x, self.heights[self.index(cell)], xz.y)
+    }
+
+    pub fn set(&mut self, cell: UVec2, height: f32, walkable: bool) {
+        let ix = self.index(cell);
+        self.heights[ix] = height;
+        self.walkable[ix] = walkable;
+    }
+
+    pub fn is_walkable(&self, cell: UVec2) -> bool {
+        self.walkable[self.index(cell)]
+    }
+
+    fn neighbors(&self, cell: UVec2) -> impl Iterator<Item = (UVec2, f32)> + '_ {
+        const OFFSETS: [IVec2; 8] = [
+            IVec2::new(1, 0),
+            IVec2::new(-1, 0),
+            IVec2::new(0, 1),
+            IVec2::new(0, -1),
+            IVec2::new(1, 1),
+            IVec2::new(1, -1),
+            IVec2::new(-1, 1),
+            IVec2::new(-1, -1),
+        ];
+        let size = self.size.as_ivec2();
+        let walkable = move |cell: IVec2| {
+            cell.cmpge(IVec2::ZERO).all()
+                && cell.cmplt(size).all()
+                && self.is_walkable(cell.as_uvec2())
+        };
+        let from = cell.as_ivec2();
+        OFFSETS.into_iter().filter_map(move |offset| {
+            let to = from + offset;
+            // Diagonals can't cut corners
+            let corners_clear = offset.x == 0
+                || offset.y == 0
+                || (walkable(from + IVec2::new(offset.x, 0))
+                    && walkable(from + IVec2::new(0, offset.y)));
+            (walkable(to) && corners_clear).then(|| (to.as_uvec2(), offset.as_vec2().length()))
+        })
+    }
+
+    /// Closest walkable cell to `cell`, searching outwards ring by ring.
+    fn nearest_walkable(&self, cell: UVec2) -> Option<UVec2> {
+        if self.is_walkable(cell) {
+            return Some(cell);
+        }
+        let max_ring = self.size.max_element() as i32;
+        let center = cell.as_ivec2();
+        (1..max_ring).find_map(|ring| {
+            let mut best: Option<(UVec2, i32)> = None;
+            for y in -ring..=ring {
+                for x in -ring..=ring {
+                    if x.abs() != ring && y.abs() != ring {
+                        continue;
+                    }
+                    let candidate = center + IVec2::new(x, y);
+                    if candidate.cmplt(IVec2::ZERO).any()
+                        || candidate.cmpge(self.size.as_ivec2()).any()
+                    {
+                        continue;
+                    }
+                    let candidate = candidate.as_uvec2();
+                    let dist = x * x + y * y;
+                    if self.is_walkable(candidate) && best.is_none_or(|(_, best)| dist < best) {
+                        best = Some((candidate, dist));
+                    }
+                }
+            }
+            best.map(|(cell, _)| cell)
+        })
+    }
+
+    /// Whether a straight line between two points only crosses walkable cells.
+    fn line_of_sight(&self, from: Vec3, to: Vec3) -> bool {
+        let steps = (from.xz().distance(to.xz()) / (self.cell_size * 0.25)).ceil() as usize;
+        (0..=steps).all(|step| {
+            let t = step as f32 / steps.max(1) as f32;
+            self.cell_at(from.lerp(to, t))
+                .is_some_and(|cell| self.is_walkable(cell))
+        })
+    }
+
+    /// Finds a path from `start` towards `goal`. If the goal can't be reached
+    /// (e.g. it's inside a tower) the path ends as close to it as possible.
+    ///
+    /// The returned waypoints don't include `start`.
+    pub fn find_path(&self, start: Vec3, goal: Vec3) -> Option<Vec<Vec3>> {
+        let start_cell = self.nearest_walkable(self.cell_at(start)?)?;
+        let goal_cell = self.cell_at(goal)?;
+        let heuristic = |cell: UVec2| {
+            let delta = (cell.as_ivec2() - goal_cell.as_ivec2()).abs().as_vec2();
+            // Octile distance
+            delta.max_element() + (std::f32::consts::SQRT_2 - 1.0) * delta.min_element()
+        };
+
+        let len = self.walkable.len();
+        let mut cost = vec![f32::INFINITY; len];
+        let mut came_from: Vec<Option<UVec2>> = vec![None; len];
+        let mut open = BinaryHeap::new();
+        cost[self.index(start_cell)] = 0.0;
+        open.push(OpenCell {
+            cell: start_cell,
+            cost: 0.0,
+            estimate: heuristic(start_cell),
+        });
+
+        let mut closest = (start_cell, heuristic(start_cell));
+        let mut expanded = 0;
+        while let Some(OpenCell {
+            cell,
+            cost: pushed_cost,
+            ..
+        }) = open.pop()
+        {
+            let cell_cost = cost[self.index(cell)];
+            // A cheaper way here was found after this entry was pushed
+            if pushed_cost > cell_cost {
+                continue;
+            }
+            let h = heuristic(cell);
+            if h < closest.1 {
+                closest = (cell, h);
+            }
+            if cell == goal_cell {
+                break;
+            }
+            expanded += 1;
+            if expanded > MAX_EXPANDED_CELLS {
+                break;
+            }
+            for (next, step_cost) in self.neighbors(cell) {
+                let next_cost = cell_cost + step_cost;
+                let ix = self.index(next);
+                if next_cost < cost[ix] {
+                    cost[ix] = next_cost;
+                    came_from[ix] = Some(cell);
+                    open.push(OpenCell {
+                        cell: next,
+                        cost: next_cost,
+                        estimate: next_cost + heuristic(next),
+                    });
+                }
+            }
+        }
+
+        let mut cells = vec![closest.0];
+        while let Some(prev) = came_from[self.index(*cells.last().unwrap())] {
+            cells.push(prev);
+        }
+        cells.reverse();
+
+        let mut points: Vec<Vec3> = cells.into_iter().map(|c| self.cell_center(c)).collect();
+        if closest.0 == goal_cell {
+            *points.last_mut().unwrap() = goal;
+        }
+        Some(self.smooth(start, points))
+    }
+
+    /// Drops waypoints that can be skipped by walking in a straight line.
+    fn smooth(&self, start: Vec3, points: Vec<Vec3>) -> Vec<Vec3> {
+        let mut smoothed = Vec::new();
+        let mut from = start;
+        let mut ix = 0;
+        while ix < points.len() {
+            let mut furthest = ix;
+            for candidate in (ix + 1..points.len()).rev() {
+                if self.line_of_sight(from, points[candidate]) {
+                    furthest = candidate;
+                    break;
+                }
+            }
+            from = points[furthest];
+            smoothed.push(from);
+            ix = furthest + 1;
+        }
+        smoothed
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct OpenCell {
+    cell: UVec2,
+    /// Cost to reach `cell` when this entry was pushed.
+    cost: f32,
+    estimate: f32,
+}
+
+impl Eq for OpenCell {}
+
+impl Ord for OpenCell {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed so the `BinaryHeap` pops the lowest estimate first
+        other.estimate.total_cmp(&self.estimate)
+    }
+}
+
+impl PartialOrd for OpenCell {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A walkable flat grid of 1x1 cells with its corner at the origin.
+    fn open_grid(size: u32) -> GridData {
+        let mut grid = GridData::new(Vec2::ZERO, 1.0, UVec2::splat(size));
+        grid.walkable.fill(true);
+        grid
+    }
+
+    fn block(grid: &mut GridData, cells: impl IntoIterator<Item = UVec2>) {
+        for cell in cells {
+            grid.set(cell, 0.0, false);
+        }
+    }
+
+    #[test]
+    fn walks_straight_to_reachable_goal() {
+        let grid = open_grid(10);
+        let goal = Vec3::new(8.5, 0.0, 0.5);
+        let path = grid.find_path(Vec3::new(0.5, 0.0, 0.5), goal).unwrap();
+        assert_eq!(path, vec![goal]);
+    }
+
+    #[test]
+    fn detours_around_blocked_cells() {
+        let mut grid = open_grid(10);
+        // Wall across x = 5, open only at the far end
+        block(&mut grid, (0..9).map(|y| UVec2::new(5, y)));
+        let start = Vec3::new(1.5, 0.0, 1.5);
+        let goal = Vec3::new(8.5, 0.0, 1.5);
+        let path = grid.find_path(start, goal).unwrap();
+
+        assert_eq!(path.last(), Some(&goal));
+        assert!(path.len() > 1, "can't go straight through the wall");
+        let mut from = start;
+        for &point in &path {
+            assert!(grid.line_of_sight(from, point), "{from} -> {point}");
+            from = point;
+        }
+    }
+
+    #[test]
+    fn stops_as_close_as_possible_to_unreachable_goal() {
+        let mut grid = open_grid(10);
+        // Box the goal's cell in
+        block(&mut grid, [UVec2::new(7, 5)]);
+        block(&mut grid, (4..7).map(|y| UVec2::new(6, y)));
+        block(&mut grid, (4..7).map(|y| UVec2::new(8, y)));
+        block(&mut grid, [UVec2::new(7, 4), UVec2::new(7, 6)]);
+        let goal = Vec3::new(7.5, 0.0, 5.5);
+        let path = grid.find_path(Vec3::new(0.5, 0.0, 5.5), goal).unwrap();
+
+        let end = *path.last().unwrap();
+        assert_ne!(end, goal);
+        assert!(grid.is_walkable(grid.cell_at(end).unwrap()));
+        assert!(end.xz().distance(goal.xz()) <= 2.0);
+    }
+}
@@ -0,0 +1,65 @@
+//! Navigation: a walkability grid baked from the level's static colliders and
+//! asynchronous A* paths over it for anything with a [`TargetEnt`].
+//!
+//! [`TargetEnt`]: crate::game::behaviors::target_ent::TargetEnt
+
+mod bake;
+#[cfg(feature = "dev")]
+mod debug;
+pub mod grid;
+pub mod path;
+
+use std::sync::Arc;
+
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+use smart_default::SmartDefault;
+
+use grid::GridData;
+
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, Reflect, SmartDefault)]
+#[reflect(Resource)]
+pub struct NavConfig {
+    #[default(10.0)]
+    pub cell_size: f32,
+    /// Obstacles are grown by this much so agents don't clip them.
+    #[default(6.0)]
+    pub agent_radius: f32,
+    #[default(30.0)]
+    pub agent_height: f32,
+    /// Obstacles lower than this above the ground are walked over.
+    #[default(2.0)]
+    pub step_height: f32,
+    #[default(0.7)]
+    pub min_ground_normal_y: f32,
+    /// Re-path once the target is this far from where the path ends.
+    #[default(20.0)]
+    pub repath_distance: f32,
+    /// Waypoints closer than this count as reached.
+    #[default(5.0)]
+    pub waypoint_radius: f32,
+    /// Wait this long after the level changes before rebaking.
+    #[default(0.2)]
+    pub rebake_delay_secs: f32,
+}
+
+/// The baked grid. Cheap to clone into path finding tasks.
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, Clone, Default, Reflect)]
+#[reflect(Resource)]
+pub struct NavGrid {
+    #[reflect(ignore)]
+    pub data: Arc<GridData>,
+    /// Bumped on every bake so paths through the old grid get replaced.
+    pub version: u32,
+}
+
+#[auto_plugin(app=app)]
+pub(crate) fn plugin(app: &mut App) {
+    app.add_plugins((bake::plugin, path::plugin));
+    #[cfg(feature = "dev")]
+    app.add_plugins(debug::plugin);
+}
@@ -0,0 +1,122 @@
+use bevy::prelude::*;
+use bevy::tasks::{AsyncComputeTaskPool, Task, futures::check_ready};
+use bevy_auto_plugin::auto_plugin::*;
+
+use super::{NavConfig, NavGrid};
+use crate::game::behaviors::MovementSpeed;
+use crate::game::behaviors::target_ent::TargetEnt;
+use crate::game::pause_controller::PausableSystems;
+
+/// Path towards a [`TargetEnt`], followed by `target_ent_sys`.
+#[auto_register_type]
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct PathFollower {
+    pub waypoints: Vec<Vec3>,
+    pub next: usize,
+    /// Where the target was when the path was requested.
+    pub goal: Vec3,
+    /// [`NavGrid::version`] the path was found on.
+    pub grid_version: u32,
+}
+
+impl PathFollower {
+    pub fn next_waypoint(&self) -> Option<Vec3> {
+        self.waypoints.get(self.next).copied()
+    }
+
+    pub fn remaining(&self) -> &[Vec3] {
+        self.waypoints.get(self.next..).unwrap_or_default()
+    }
+
+    /// Moves on to the next waypoint once `position` is within `radius` of
+    /// the current one (ignoring height).
+    pub fn advance(&mut self, position: Vec3, radius: f32) {
+        while let Some(waypoint) = self.next_waypoint() {
+            if waypoint.xz().distance(position.xz()) > radius {
+                break;
+            }
+            self.next += 1;
+        }
+    }
+}
+
+/// A path being found in the background.
+#[derive(Component)]
+struct PathTask {
+    task: Task<Option<Vec<Vec3>>>,
+    goal: Vec3,
+    grid_version: u32,
+}
+
+#[auto_plugin(app=app)]
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (request_paths, receive_paths, clear_stale_paths).in_set(PausableSystems),
+    );
+}
+
+fn request_paths(
+    mut commands: Commands,
+    grid: Res<NavGrid>,
+    cfg: Res<NavConfig>,
+    agents: Query<
+        (Entity, &TargetEnt, &GlobalTransform, Option<&PathFollower>),
+        (With<MovementSpeed>, Without<PathTask>),
+    >,
+    targets: Query<&GlobalTransform>,
+) {
+    if grid.data.walkable.is_empty() {
+        return;
+    }
+    for (entity, target, gt, follower) in agents.iter() {
+        let Ok(target_gt) = targets.get(target.target_ent) else {
+            continue;
+        };
+        let goal = target_gt.translation();
+        let up_to_date = follower.is_some_and(|follower| {
+            follower.grid_version == grid.version
+                && follower.goal.distance(goal) < cfg.repath_distance
+        });
+        if up_to_date {
+            continue;
+        }
+        let data = grid.data.clone();
+        let start = gt.translation();
+        let task = AsyncComputeTaskPool::get().spawn(async move { data.find_path(start, goal) });
+        commands.entity(entity).insert(PathTask {
+            task,
+            goal,
+            grid_version: grid.version,
+        });
+    }
+}
+
+fn receive_paths(mut commands: Commands, mut tasks: Query<(Entity, &mut PathTask)>) {
+    for (entity, mut path_task) in tasks.iter_mut() {
+        let Some(waypoints) = check_ready(&mut path_task.task) else {
+            continue;
+        };
+        // An empty path (e.g. when off the grid) heads straight for the target
+        commands
+            .entity(entity)
+            .remove::<PathTask>()
+            .insert(PathFollower {
+                waypoints: waypoints.unwrap_or_default(),
+                next: 0,
+                goal: path_task.goal,
+                grid_version: path_task.grid_version,
+            });
+    }
+}
+
+/// Drops paths and pending requests of anything that stopped chasing.
+fn clear_stale_paths(
+    mut commands: Commands,
+    stale: Query<Entity, (Or<(With<PathFollower>, With<PathTask>)>, Without<TargetEnt>)>,
+) {
+    for entity in stale.iter() {
+        commands.entity(entity).remove::<(PathFollower, PathTask)>();
+    }
+}
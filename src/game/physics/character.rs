@@ -0,0 +1,228 @@
+//! Kinematic character controller: moves characters by their
+//! [`DesiredVelocity`] using shape casts against static geometry, with gravity,
+//! ground snapping, slope limits and collide-and-slide.
+
+use avian3d::prelude::{
+    Collider, RigidBody, ShapeCastConfig, ShapeHitData, SpatialQuery, SpatialQueryFilter,
+};
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+use smart_default::SmartDefault;
+
+use crate::game::constants::METERS_PER_UNIT;
+use crate::game::pause_controller::PausableSystems;
+use crate::game::physics::knockback::{self, KnockbackVelocity};
+use crate::game::physics::layers::GameLayer;
+
+/// Velocity a character wants to move at, applied by its
+/// [`CharacterController`] or straight to its [`Transform`] without one.
+#[auto_register_type]
+#[derive(Component, Debug, Default, Copy, Clone, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct DesiredVelocity(pub Vec3);
+
+#[auto_register_type]
+#[derive(Component, Debug, Clone, Reflect, SmartDefault)]
+#[reflect(Component)]
+#[require(
+    DesiredVelocity,
+    CharacterState,
+    KnockbackVelocity,
+    RigidBody = RigidBody::Kinematic
+)]
+pub struct CharacterController {
+    #[default(9.81 / METERS_PER_UNIT)]
+    pub gravity: f32,
+    #[default(45.0)]
+    pub max_slope_deg: f32,
+    /// Stay glued to the ground when it's at most this far below.
+    #[default(5.0)]
+    pub snap_distance: f32,
+    /// Gap kept between the collider and whatever it's touching.
+    #[default(0.1)]
+    pub skin: f32,
+    #[default(4)]
+    pub max_slides: usize,
+    /// How quickly [`KnockbackVelocity`] dies down, per second.
+    #[default(6.0)]
+    pub knockback_drag: f32,
+}
+
+impl CharacterController {
+    fn is_walkable(&self, normal: Vec3) -> bool {
+        normal.y >= self.max_slope_deg.to_radians().cos()
+    }
+}
+
+#[auto_register_type]
+#[derive(Component, Debug, Default, Copy, Clone, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct CharacterState {
+    pub vertical_speed: f32,
+    pub grounded: bool,
+    pub ground_normal: Vec3,
+}
+
+#[derive(SystemSet, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct CharacterControllerSystems;
+
+#[auto_plugin(app=app)]
+pub(crate) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (move_characters, move_uncontrolled)
+            .in_set(CharacterControllerSystems)
+            .in_set(PausableSystems),
+    );
+}
+
+fn move_characters(
+    time: Res<Time>,
+    spatial_query: SpatialQuery,
+    mut characters: Query<(
+        Entity,
+        &CharacterController,
+        &DesiredVelocity,
+        &mut KnockbackVelocity,
+        &mut CharacterState,
+        &mut Transform,
+        &Collider,
+    )>,
+) {
+    let dt = time.delta_secs();
+    if dt <= 0.0 {
+        return;
+    }
+    for (entity, controller, desired, mut knockback, mut state, mut transform, collider) in
+        characters.iter_mut()
+    {
+        let rotation = transform.rotation;
+        if state.grounded {
+            state.vertical_speed = 0.0;
+        } else {
+            state.vertical_speed -= controller.gravity * dt;
+        }
+        // Walk along the ground rather than into or off it
+        let mut horizontal = (desired.0 + knockback.0).with_y(0.0);
+        knockback::decay(&mut knockback, controller.knockback_drag, dt);
+        if state.grounded {
+            horizontal = horizontal.reject_from_normalized(state.ground_normal);
+        }
+        let motion = (horizontal + Vec3::Y * state.vertical_speed) * dt;
+
+        let mut position = collide_and_slide(
+            &spatial_query,
+            collider,
+            entity,
+            rotation,
+            transform.translation,
+            motion,
+            controller.skin,
+            controller.max_slides,
+            |normal| {
+                if normal.y > 0.0 && state.vertical_speed < 0.0 {
+                    state.vertical_speed = 0.0;
+                }
+                // Too steep to climb, so slide as if it were a vertical wall
+                if controller.is_walkable(normal) {
+                    normal
+                } else {
+                    normal.with_y(0.0).normalize_or(normal)
+                }
+            },
+        );
+
+        // Look for ground below and snap down onto it
+        let ground = cast_against_level(
+            &spatial_query,
+            collider,
+            entity,
+            rotation,
+            position,
+            Vec3::NEG_Y * controller.snap_distance,
+            controller.skin,
+        )
+        .filter(|(_, hit)| controller.is_walkable(hit.normal1));
+        match ground {
+            Some((_, hit)) if state.vertical_speed <= 0.0 => {
+                position.y -= (hit.distance - controller.skin).max(0.0);
+                state.grounded = true;
+                state.ground_normal = hit.normal1;
+            }
+            _ => {
+                state.grounded = false;
+                state.ground_normal = Vec3::Y;
+            }
+        }
+
+        transform.translation = position;
+    }
+}
+
+/// Casts `collider` along `motion` against the level, reaching `skin` further
+/// so it stops short of whatever it hits.
+pub fn cast_against_level(
+    spatial_query: &SpatialQuery,
+    collider: &Collider,
+    entity: Entity,
+    rotation: Quat,
+    origin: Vec3,
+    motion: Vec3,
+    skin: f32,
+) -> Option<(Dir3, ShapeHitData)> {
+    // Only the level blocks movement, characters are steered around each other
+    let filter = SpatialQueryFilter::from_mask([GameLayer::Terrain, GameLayer::Tower])
+        .with_excluded_entities([entity]);
+    let (direction, length) = Dir3::new_and_length(motion).ok()?;
+    let config = ShapeCastConfig {
+        max_distance: length + skin,
+        ignore_origin_penetration: true,
+        ..ShapeCastConfig::DEFAULT
+    };
+    spatial_query
+        .cast_shape(collider, origin, rotation, direction, &config, &filter)
+        .map(|hit| (direction, hit))
+}
+
+/// Moves `collider` from `position` by `motion`, sliding along the level rather
+/// than going through it, and returns where it ends up. Every hit's normal goes
+/// through `slide_normal`, which returns the plane to slide along instead.
+pub fn collide_and_slide(
+    spatial_query: &SpatialQuery,
+    collider: &Collider,
+    entity: Entity,
+    rotation: Quat,
+    mut position: Vec3,
+    mut motion: Vec3,
+    skin: f32,
+    max_slides: usize,
+    mut slide_normal: impl FnMut(Vec3) -> Vec3,
+) -> Vec3 {
+    for _ in 0..max_slides {
+        let Some((direction, hit)) = cast_against_level(
+            spatial_query,
+            collider,
+            entity,
+            rotation,
+            position,
+            motion,
+            skin,
+        ) else {
+            return position + motion;
+        };
+        let travel = (hit.distance - skin).max(0.0);
+        position += direction * travel;
+        let remaining = motion - direction * travel;
+        motion = remaining.reject_from_normalized(slide_normal(hit.normal1));
+    }
+    position
+}
+
+fn move_uncontrolled(
+    time: Res<Time>,
+    mut movers: Query<(&DesiredVelocity, &mut Transform), Without<CharacterController>>,
+) {
+    for (desired, mut transform) in movers.iter_mut() {
+        transform.translation += desired.0 * time.delta_secs();
+    }
+}
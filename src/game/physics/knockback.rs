@@ -0,0 +1,141 @@
+//! Pushing things around. Send a [`Knockback`] and it gets applied through the
+//! target's [`CharacterController`](super::character::CharacterController), or
+//! as an impulse on dynamic bodies, scaled by the target's [`Mass`] and
+//! [`KnockbackResistance`]. Hard enough hits also stun the target for a moment,
+//! which suspends its [`TargetEnt`](crate::game::behaviors::target_ent::TargetEnt)
+//! movement.
+
+use avian3d::prelude::{ExternalImpulse, Mass, RigidBody};
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+use smart_default::SmartDefault;
+
+use super::character::{CharacterControllerSystems, CharacterState, DesiredVelocity};
+use crate::game::behaviors::state_machine::Stun;
+use crate::game::pause_controller::PausableSystems;
+
+/// Request to push `target` along `direction` with an impulse of `magnitude`.
+#[auto_add_event]
+#[derive(Event, Debug, Copy, Clone, Reflect)]
+pub struct Knockback {
+    pub target: Entity,
+    pub direction: Vec3,
+    pub magnitude: f32,
+}
+
+impl Knockback {
+    pub fn new(target: Entity, direction: Vec3, magnitude: f32) -> Self {
+        Self {
+            target,
+            direction,
+            magnitude,
+        }
+    }
+}
+
+/// Fraction of every knockback that's ignored, `1.0` being immune.
+#[auto_register_type]
+#[derive(Component, Debug, Default, Copy, Clone, Reflect)]
+#[reflect(Component)]
+pub struct KnockbackResistance(pub f32);
+
+/// Horizontal velocity from knockbacks, added on top of the
+/// [`DesiredVelocity`](super::character::DesiredVelocity) by the
+/// [`CharacterController`](super::character::CharacterController) and slowed
+/// down by its drag.
+#[auto_register_type]
+#[derive(Component, Debug, Default, Copy, Clone, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct KnockbackVelocity(pub Vec3);
+
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, Reflect, SmartDefault)]
+#[reflect(Resource)]
+pub struct KnockbackConfig {
+    /// Mass of anything without a [`Mass`].
+    #[default(1.0)]
+    pub default_mass: f32,
+    /// Knockbacks changing velocity by at least this much stun the target.
+    #[default(20.0)]
+    pub min_stun_speed: f32,
+    #[default(0.3)]
+    pub hit_stun_secs: f32,
+}
+
+#[auto_plugin(app=app)]
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        apply_knockback
+            .before(CharacterControllerSystems)
+            .in_set(PausableSystems),
+    );
+}
+
+fn apply_knockback(
+    mut commands: Commands,
+    mut knockbacks: EventReader<Knockback>,
+    cfg: Res<KnockbackConfig>,
+    mut targets: Query<(
+        Option<&Mass>,
+        Option<&KnockbackResistance>,
+        Option<&RigidBody>,
+        Option<(&mut KnockbackVelocity, &mut CharacterState)>,
+        Option<&mut ExternalImpulse>,
+        Option<&mut DesiredVelocity>,
+    )>,
+) {
+    for knockback in knockbacks.read() {
+        let Ok((mass, resistance, rigid_body, character, impulse, desired)) =
+            targets.get_mut(knockback.target)
+        else {
+            continue;
+        };
+        let mass = mass
+            .map_or(cfg.default_mass, |mass| mass.0)
+            .max(f32::EPSILON);
+        let resistance = resistance.map_or(0.0, |resistance| resistance.0.clamp(0.0, 1.0));
+        let velocity_change = knockback.direction.normalize_or_zero() * knockback.magnitude / mass
+            * (1.0 - resistance);
+        if velocity_change == Vec3::ZERO {
+            continue;
+        }
+
+        if let Some((mut velocity, mut state)) = character {
+            velocity.0 += velocity_change.with_y(0.0);
+            if velocity_change.y > 0.0 {
+                state.vertical_speed += velocity_change.y;
+                state.grounded = false;
+            }
+        } else if rigid_body == Some(&RigidBody::Dynamic) {
+            match impulse {
+                Some(mut impulse) => {
+                    impulse.apply_impulse(velocity_change * mass);
+                }
+                None => {
+                    commands
+                        .entity(knockback.target)
+                        .insert(ExternalImpulse::new(velocity_change * mass));
+                }
+            }
+        }
+
+        if velocity_change.length() >= cfg.min_stun_speed {
+            commands
+                .entity(knockback.target)
+                .insert(Stun::secs(cfg.hit_stun_secs));
+            if let Some(mut desired) = desired {
+                desired.0 = Vec3::ZERO;
+            }
+        }
+    }
+}
+
+/// Slows knockback down the way friction would.
+pub(super) fn decay(velocity: &mut KnockbackVelocity, drag: f32, dt: f32) {
+    velocity.0 *= (-drag * dt).exp();
+    if velocity.0.length_squared() < 1e-4 {
+        velocity.0 = Vec3::ZERO;
+    }
+}
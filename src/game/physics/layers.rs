@@ -0,0 +1,54 @@
+use avian3d::prelude::{CollisionLayers, PhysicsLayer};
+
+#[derive(PhysicsLayer, Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum GameLayer {
+    #[default]
+    Default,
+    Terrain,
+    Enemy,
+    Tower,
+    /// Sensors only detect, nothing collides against them.
+    Sensor,
+}
+
+impl GameLayer {
+    pub fn terrain() -> CollisionLayers {
+        CollisionLayers::new(
+            GameLayer::Terrain,
+            [GameLayer::Default, GameLayer::Enemy, GameLayer::Tower],
+        )
+    }
+
+    pub fn enemy() -> CollisionLayers {
+        CollisionLayers::new(
+            GameLayer::Enemy,
+            [
+                GameLayer::Default,
+                GameLayer::Terrain,
+                GameLayer::Enemy,
+                GameLayer::Tower,
+                GameLayer::Sensor,
+            ],
+        )
+    }
+
+    pub fn tower() -> CollisionLayers {
+        CollisionLayers::new(
+            GameLayer::Tower,
+            [
+                GameLayer::Default,
+                GameLayer::Terrain,
+                GameLayer::Enemy,
+                GameLayer::Sensor,
+            ],
+        )
+    }
+
+    /// Senses enemies, towers and conduits, but never terrain.
+    pub fn sensor() -> CollisionLayers {
+        CollisionLayers::new(
+            GameLayer::Sensor,
+            [GameLayer::Default, GameLayer::Enemy, GameLayer::Tower],
+        )
+    }
+}
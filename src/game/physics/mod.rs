@@ -1,3 +1,7 @@
+pub mod character;
+pub mod knockback;
+pub mod layers;
+
 use crate::game::pause_controller::Pause;
 use avian3d::prelude::{
     Physics, PhysicsInterpolationPlugin, PhysicsPickingPlugin, PhysicsPlugins, PhysicsTime,
@@ -32,6 +36,7 @@ fn toggle_gizmos(
 pub(crate) fn plugin(app: &mut App) {
     app.add_plugins(PhysicsPlugins::default().set(PhysicsInterpolationPlugin::extrapolate_all()));
     app.add_plugins(PhysicsPickingPlugin);
+    app.add_plugins((character::plugin, knockback::plugin));
     #[cfg(feature = "dev")]
     {
         app.add_plugins(PhysicsDebugPlugin::default());
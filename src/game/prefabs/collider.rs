@@ -0,0 +1,108 @@
+use avian3d::prelude::Collider;
+use bevy::math::Affine3A;
+use bevy::prelude::*;
+use bevy::scene::SceneInstanceReady;
+use bevy_auto_plugin::auto_plugin::*;
+
+/// How to fit a [`Collider`] to a prefab's model once its scene has been
+/// instanced. The collider is inserted on the prefab's root entity, replacing
+/// any existing one.
+#[auto_register_type]
+#[derive(Component, Debug, Default, Copy, Clone, PartialEq, Reflect)]
+#[reflect(Component)]
+pub enum PrefabCollider {
+    /// Convex hull of every mesh vertex.
+    #[default]
+    ConvexHull,
+    /// Upright capsule fitted to the mesh bounds.
+    Capsule,
+}
+
+impl PrefabCollider {
+    fn build(&self, points: Vec<Vec3>) -> Option<Collider> {
+        match self {
+            Self::ConvexHull => Collider::convex_hull(points),
+            Self::Capsule => {
+                let min = points.iter().copied().reduce(Vec3::min)?;
+                let max = points.iter().copied().reduce(Vec3::max)?;
+                let extents = max - min;
+                let radius = extents.x.max(extents.z) / 2.0;
+                let length = (extents.y - radius * 2.0).max(0.0);
+                Some(Collider::compound(vec![(
+                    (min + max) / 2.0,
+                    Quat::IDENTITY,
+                    Collider::capsule(radius, length),
+                )]))
+            }
+        }
+    }
+}
+
+/// Collects every mesh vertex below `entity`, in `entity`'s local space.
+///
+/// [`GlobalTransform`]s haven't been propagated yet when a scene is instanced,
+/// so this accumulates [`Transform`]s down the hierarchy instead.
+fn collect_mesh_points(
+    entity: Entity,
+    to_root: Affine3A,
+    points: &mut Vec<Vec3>,
+    children_q: &Query<&Children>,
+    transforms_q: &Query<(&Transform, Option<&Mesh3d>)>,
+    meshes: &Assets<Mesh>,
+) {
+    let Ok(children) = children_q.get(entity) else {
+        return;
+    };
+    for &child in children {
+        let Ok((transform, mesh3d)) = transforms_q.get(child) else {
+            continue;
+        };
+        let to_root = to_root * transform.compute_affine();
+        let positions = mesh3d
+            .and_then(|mesh3d| meshes.get(&mesh3d.0))
+            .and_then(|mesh| mesh.attribute(Mesh::ATTRIBUTE_POSITION))
+            .and_then(|positions| positions.as_float3());
+        if let Some(positions) = positions {
+            points.extend(
+                positions
+                    .iter()
+                    .map(|&position| to_root.transform_point3(Vec3::from(position))),
+            );
+        }
+        collect_mesh_points(child, to_root, points, children_q, transforms_q, meshes);
+    }
+}
+
+fn on_scene_instance_ready(
+    trigger: Trigger<SceneInstanceReady>,
+    mut commands: Commands,
+    prefab_q: Query<(NameOrEntity, &PrefabCollider)>,
+    children_q: Query<&Children>,
+    transforms_q: Query<(&Transform, Option<&Mesh3d>)>,
+    meshes: Res<Assets<Mesh>>,
+) {
+    let Ok((name, prefab_collider)) = prefab_q.get(trigger.target()) else {
+        return;
+    };
+
+    let mut points = Vec::new();
+    collect_mesh_points(
+        name.entity,
+        Affine3A::IDENTITY,
+        &mut points,
+        &children_q,
+        &transforms_q,
+        &meshes,
+    );
+
+    let Some(collider) = prefab_collider.build(points) else {
+        error!("Failed to fit a {prefab_collider:?} collider for {name}");
+        return;
+    };
+    commands.entity(name.entity).insert(collider);
+}
+
+#[auto_plugin(app=app)]
+pub(crate) fn plugin(app: &mut App) {
+    app.add_observer(on_scene_instance_ready);
+}
@@ -1,8 +1,20 @@
 use crate::game::asset_tracking::LoadResource;
+use crate::game::currency::Bounty;
+use crate::game::health::{Health, MaxHealth};
+use crate::game::physics::character::CharacterController;
+use crate::game::physics::knockback::KnockbackResistance;
+use crate::game::physics::layers::GameLayer;
+use crate::game::prefabs::collider::PrefabCollider;
+use crate::game::prefabs::pending::{PendingPrefabScene, PrefabPlaceholder};
+use avian3d::prelude::Mass;
 use bevy::prelude::*;
 use bevy_auto_plugin::auto_plugin::*;
 
 use crate::game::behaviors::MovementSpeed;
+use crate::game::behaviors::attack::Attack;
+use crate::game::behaviors::avoidance::AvoidanceAgent;
+use crate::game::behaviors::state_machine::BehaviorState;
+use crate::game::behaviors::targeting::Targeting;
 
 #[auto_register_type]
 #[derive(Resource, Asset, Debug, Clone, Reflect)]
@@ -35,6 +47,58 @@ impl Enemy {
             Self::BaseSkele => 8.0,
         }
     }
+
+    pub fn max_health(&self) -> f32 {
+        match self {
+            Self::BaseSkele => 100.0,
+        }
+    }
+
+    pub fn attack_damage(&self) -> f32 {
+        match self {
+            Self::BaseSkele => 10.0,
+        }
+    }
+
+    pub fn attack_secs(&self) -> f32 {
+        match self {
+            Self::BaseSkele => 1.5,
+        }
+    }
+
+    pub fn bounty(&self) -> u32 {
+        match self {
+            Self::BaseSkele => 10,
+        }
+    }
+
+    pub fn mass(&self) -> f32 {
+        match self {
+            Self::BaseSkele => 50.0,
+        }
+    }
+
+    pub fn knockback_resistance(&self) -> f32 {
+        match self {
+            Self::BaseSkele => 0.2,
+        }
+    }
+
+    pub fn collider(&self) -> PrefabCollider {
+        match self {
+            Self::BaseSkele => PrefabCollider::Capsule,
+        }
+    }
+
+    /// Shape spawned in place of the model if it fails to load.
+    pub fn placeholder(&self) -> PrefabPlaceholder {
+        match self {
+            Self::BaseSkele => PrefabPlaceholder {
+                radius: 0.4,
+                length: 1.2,
+            },
+        }
+    }
 }
 
 #[auto_plugin(app=app)]
@@ -47,26 +111,37 @@ fn on_enemy_added(
     trigger: Trigger<OnAdd, Enemy>,
     query: Query<&Enemy>,
     enemy_assets: Res<EnemyAssets>,
-    gltfs: Res<Assets<Gltf>>,
     mut commands: Commands,
 ) {
     let enemy = query
         .get(trigger.target())
         .expect("No target entity for trigger");
 
-    // Model handle
+    // Model handle, the scene is spawned once the gltf finishes loading
     let gltf_h = match *enemy {
         Enemy::BaseSkele => enemy_assets.base_skele.clone(),
     };
-    let gltf = gltfs
-        .get(&gltf_h)
-        .unwrap_or_else(|| panic!("Missing gltf asset for {:?}", enemy));
 
     // MovementSpeed
     let movement_speed = MovementSpeed(enemy.default_move_speed());
 
     commands
         .entity(trigger.target())
-        .insert(SceneRoot(gltf.scenes[0].clone()))
-        .insert(movement_speed);
+        .insert(PendingPrefabScene::gltf(gltf_h).with_placeholder(enemy.placeholder()))
+        .insert((enemy.collider(), GameLayer::enemy()))
+        .insert((
+            Health(enemy.max_health()),
+            MaxHealth(enemy.max_health()),
+            Bounty(enemy.bounty()),
+        ))
+        .insert((
+            movement_speed,
+            AvoidanceAgent::default(),
+            CharacterController::default(),
+            BehaviorState::default(),
+            Targeting::default(),
+            Attack::new(enemy.attack_damage(), enemy.attack_secs()),
+            Mass(enemy.mass()),
+            KnockbackResistance(enemy.knockback_resistance()),
+        ));
 }
@@ -1,4 +1,7 @@
+pub mod collider;
 pub mod enemy;
+pub mod pending;
+pub mod socket;
 pub mod tower;
 pub mod wizard;
 
@@ -7,7 +10,10 @@ use bevy_auto_plugin::auto_plugin::*;
 
 #[auto_plugin(app=app)]
 pub(crate) fn plugin(app: &mut App) {
+    app.add_plugins(collider::plugin);
     app.add_plugins(enemy::plugin);
+    app.add_plugins(pending::plugin);
+    app.add_plugins(socket::plugin);
     app.add_plugins(tower::plugin);
     app.add_plugins(wizard::plugin);
 }
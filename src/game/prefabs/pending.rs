@@ -0,0 +1,172 @@
+use avian3d::prelude::Collider;
+use bevy::asset::LoadState;
+use bevy::color::palettes::css::MAGENTA;
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+
+/// Where a prefab's model comes from.
+#[derive(Debug, Clone, Reflect)]
+pub enum PrefabSceneSource {
+    /// Spawns the first scene of the gltf.
+    Gltf(Handle<Gltf>),
+    Scene(Handle<Scene>),
+}
+
+/// Capsule used in place of a prefab's model when it fails to load.
+#[derive(Debug, Copy, Clone, Reflect)]
+pub struct PrefabPlaceholder {
+    pub radius: f32,
+    pub length: f32,
+}
+
+impl Default for PrefabPlaceholder {
+    fn default() -> Self {
+        Self {
+            radius: 0.5,
+            length: 1.0,
+        }
+    }
+}
+
+impl PrefabPlaceholder {
+    fn height(&self) -> f32 {
+        self.length + self.radius * 2.0
+    }
+}
+
+/// Queues a [`SceneRoot`] until its asset has finished loading.
+///
+/// If the asset fails to load, a [`PrefabPlaceholder`] mesh is spawned as a
+/// child instead, with a matching collider on the entity itself.
+#[auto_register_type]
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct PendingPrefabScene {
+    pub source: PrefabSceneSource,
+    pub placeholder: PrefabPlaceholder,
+}
+
+impl PendingPrefabScene {
+    pub fn gltf(handle: Handle<Gltf>) -> Self {
+        Self {
+            source: PrefabSceneSource::Gltf(handle),
+            placeholder: PrefabPlaceholder::default(),
+        }
+    }
+
+    pub fn scene(handle: Handle<Scene>) -> Self {
+        Self {
+            source: PrefabSceneSource::Scene(handle),
+            placeholder: PrefabPlaceholder::default(),
+        }
+    }
+
+    pub fn with_placeholder(mut self, placeholder: PrefabPlaceholder) -> Self {
+        self.placeholder = placeholder;
+        self
+    }
+}
+
+/// Marks the child spawned in place of a prefab's model.
+#[auto_register_type]
+#[auto_name]
+#[derive(Component, Debug, Default, Copy, Clone, Reflect)]
+#[reflect(Component)]
+pub struct PrefabPlaceholderMesh;
+
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct PrefabPlaceholderMaterialCache(Handle<StandardMaterial>);
+
+impl FromWorld for PrefabPlaceholderMaterialCache {
+    fn from_world(world: &mut World) -> Self {
+        let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
+        Self(materials.add(StandardMaterial {
+            base_color: MAGENTA.into(),
+            perceptual_roughness: 1.0,
+            ..Default::default()
+        }))
+    }
+}
+
+enum Resolved {
+    Scene(Handle<Scene>),
+    Failed(String),
+    Waiting,
+}
+
+fn resolve(
+    source: &PrefabSceneSource,
+    asset_server: &AssetServer,
+    gltfs: &Assets<Gltf>,
+) -> Resolved {
+    match source {
+        PrefabSceneSource::Gltf(handle) => {
+            if let Some(gltf) = gltfs.get(handle) {
+                return match gltf.scenes.first() {
+                    Some(scene) => Resolved::Scene(scene.clone()),
+                    None => Resolved::Failed("gltf has no scenes".to_string()),
+                };
+            }
+            match asset_server.load_state(handle) {
+                LoadState::Failed(err) => Resolved::Failed(err.to_string()),
+                _ => Resolved::Waiting,
+            }
+        }
+        PrefabSceneSource::Scene(handle) => match asset_server.load_state(handle) {
+            LoadState::Loaded => Resolved::Scene(handle.clone()),
+            LoadState::Failed(err) => Resolved::Failed(err.to_string()),
+            _ => Resolved::Waiting,
+        },
+    }
+}
+
+fn spawn_pending_prefab_scenes(
+    mut commands: Commands,
+    pending_q: Query<(NameOrEntity, &PendingPrefabScene)>,
+    asset_server: Res<AssetServer>,
+    gltfs: Res<Assets<Gltf>>,
+    material_cache: Res<PrefabPlaceholderMaterialCache>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    for (name, pending) in pending_q.iter() {
+        let entity = name.entity;
+        match resolve(&pending.source, &asset_server, &gltfs) {
+            Resolved::Waiting => continue,
+            Resolved::Scene(scene) => {
+                commands
+                    .entity(entity)
+                    .remove::<PendingPrefabScene>()
+                    .insert(SceneRoot(scene));
+            }
+            Resolved::Failed(reason) => {
+                error!("Failed to load model for {name}, using placeholder: {reason}");
+                let placeholder = pending.placeholder;
+                let offset = Vec3::Y * placeholder.height() / 2.0;
+                // The collider goes on the root so it stands in for the one
+                // `PrefabCollider` would have fitted to the model.
+                commands
+                    .entity(entity)
+                    .remove::<PendingPrefabScene>()
+                    .insert(Collider::compound(vec![(
+                        offset,
+                        Quat::IDENTITY,
+                        Collider::capsule(placeholder.radius, placeholder.length),
+                    )]))
+                    .with_child((
+                        PrefabPlaceholderMesh,
+                        Mesh3d(meshes.add(Capsule3d::new(placeholder.radius, placeholder.length))),
+                        MeshMaterial3d(material_cache.0.clone()),
+                        Transform::from_translation(offset),
+                    ));
+            }
+        }
+    }
+}
+
+#[auto_plugin(app=app)]
+pub(crate) fn plugin(app: &mut App) {
+    app.add_systems(PreUpdate, spawn_pending_prefab_scenes);
+}
@@ -0,0 +1,129 @@
+use bevy::prelude::*;
+use bevy::scene::SceneInstanceReady;
+use bevy_auto_plugin::auto_plugin::*;
+use std::borrow::Cow;
+
+/// Re-parents this entity onto the gltf node (or bone) named `node` once the
+/// [`SceneRoot`] it's a child of finishes instancing, so it follows the model
+/// when it animates. The entity's [`Transform`] is kept as the offset from the
+/// node.
+#[auto_register_type]
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+#[require(Transform)]
+pub struct AttachToSocket {
+    pub node: Cow<'static, str>,
+}
+
+impl AttachToSocket {
+    pub fn new(node: impl Into<Cow<'static, str>>) -> Self {
+        Self { node: node.into() }
+    }
+}
+
+/// Inserted on a [`SceneRoot`] once its scene has been instanced and its
+/// sockets can be resolved.
+#[auto_register_type]
+#[derive(Component, Debug, Default, Copy, Clone, Reflect)]
+#[reflect(Component)]
+pub struct SocketsReady;
+
+/// The node an [`AttachToSocket`] entity was re-parented onto.
+#[auto_register_type]
+#[derive(Component, Debug, Copy, Clone, Reflect)]
+#[reflect(Component)]
+pub struct AttachedToSocket(pub Entity);
+
+fn find_node(
+    scene_root: Entity,
+    node: &str,
+    children_q: &Query<&Children>,
+    names_q: &Query<&Name>,
+) -> Option<Entity> {
+    children_q.iter_descendants(scene_root).find(|&descendant| {
+        names_q
+            .get(descendant)
+            .is_ok_and(|name| name.as_str() == node)
+    })
+}
+
+fn attach(
+    commands: &mut Commands,
+    scene_root: Entity,
+    attachment: Entity,
+    socket: &AttachToSocket,
+    children_q: &Query<&Children>,
+    names_q: &Query<&Name>,
+) {
+    let Some(node) = find_node(scene_root, &socket.node, children_q, names_q) else {
+        warn!(
+            "No socket node named {:?} under {scene_root}, leaving {attachment} in place",
+            socket.node
+        );
+        return;
+    };
+    commands
+        .entity(attachment)
+        .insert((ChildOf(node), AttachedToSocket(node)));
+}
+
+fn on_scene_instance_ready(
+    trigger: Trigger<SceneInstanceReady>,
+    mut commands: Commands,
+    sockets_q: Query<(Entity, &AttachToSocket), Without<AttachedToSocket>>,
+    children_q: Query<&Children>,
+    names_q: Query<&Name>,
+) {
+    let scene_root = trigger.target();
+    if scene_root == Entity::PLACEHOLDER {
+        return;
+    }
+    commands.entity(scene_root).insert(SocketsReady);
+
+    let Ok(children) = children_q.get(scene_root) else {
+        return;
+    };
+    for (attachment, socket) in sockets_q.iter_many(children) {
+        attach(
+            &mut commands,
+            scene_root,
+            attachment,
+            socket,
+            &children_q,
+            &names_q,
+        );
+    }
+}
+
+/// Handles attachments added after the scene was already instanced.
+fn on_attach_to_socket_added(
+    trigger: Trigger<OnAdd, AttachToSocket>,
+    mut commands: Commands,
+    sockets_q: Query<(&AttachToSocket, &ChildOf)>,
+    ready_q: Query<(), With<SocketsReady>>,
+    children_q: Query<&Children>,
+    names_q: Query<&Name>,
+) {
+    let attachment = trigger.target();
+    let Ok((socket, child_of)) = sockets_q.get(attachment) else {
+        return;
+    };
+    let scene_root = child_of.parent();
+    if !ready_q.contains(scene_root) {
+        return;
+    }
+    attach(
+        &mut commands,
+        scene_root,
+        attachment,
+        socket,
+        &children_q,
+        &names_q,
+    );
+}
+
+#[auto_plugin(app=app)]
+pub(crate) fn plugin(app: &mut App) {
+    app.add_observer(on_scene_instance_ready);
+    app.add_observer(on_attach_to_socket_added);
+}
@@ -1,34 +1,208 @@
+use crate::game::constants::METERS_PER_UNIT;
+use crate::game::despawn::DespawnDelayed;
+use crate::game::health::{AdjustHp, Dead, Health, MaxHealth};
+use crate::game::pause_controller::PausableSystems;
+use crate::game::physics::layers::GameLayer;
+use crate::game::spark::Spark;
 use avian3d::prelude::Collider;
 use bevy::color::palettes::css::GRAY;
 use bevy::prelude::*;
 use bevy_auto_plugin::auto_plugin::*;
+use smart_default::SmartDefault;
 
+/// A tower, with its origin at the base.
 #[auto_register_type]
 #[auto_name]
 #[derive(Component, Debug, Default, Copy, Clone, Reflect)]
 #[reflect(Component)]
+#[require(Transform)]
+#[require(TowerLevel)]
 pub struct Tower;
 
+/// Upgrade level of a [`Tower`], starting at 0. Inserting a new level rebuilds
+/// the tower's [`TowerStats`], mesh and collider.
+#[auto_register_type]
+#[derive(Component, Debug, Default, Copy, Clone, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub struct TowerLevel(pub u32);
+
+/// Children with this are kept on top of their [`Tower`] as it grows.
+#[auto_register_type]
+#[derive(Component, Debug, Default, Copy, Clone, Reflect)]
+#[reflect(Component)]
+#[require(Transform)]
+pub struct TowerTop;
+
+#[auto_register_type]
+#[derive(Component, Debug, Copy, Clone, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct TowerStats {
+    pub height: f32,
+    /// Sparks within this range are recharged by the tower.
+    pub conduit_range_m: f32,
+    pub charge_regen_per_second: f32,
+    pub max_health: f32,
+}
+
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, Reflect, SmartDefault)]
+#[reflect(Resource)]
+pub struct TowerConfig {
+    #[default(10.0)]
+    pub radius: f32,
+    #[default(100.0)]
+    pub base_height: f32,
+    #[default(25.0)]
+    pub height_per_level: f32,
+    #[default(20.0)]
+    pub base_conduit_range_m: f32,
+    #[default(10.0)]
+    pub conduit_range_per_level_m: f32,
+    #[default(2.0)]
+    pub base_charge_regen_per_second: f32,
+    #[default(2.0)]
+    pub charge_regen_per_level: f32,
+    #[default(500.0)]
+    pub base_max_health: f32,
+    #[default(150.0)]
+    pub max_health_per_level: f32,
+    #[default(3)]
+    pub max_level: u32,
+    #[default(50)]
+    pub build_cost: u32,
+    #[default(40)]
+    pub upgrade_cost_per_level: u32,
+}
+
+impl TowerConfig {
+    pub fn stats(&self, level: TowerLevel) -> TowerStats {
+        let level = level.0 as f32;
+        TowerStats {
+            height: self.base_height + self.height_per_level * level,
+            conduit_range_m: self.base_conduit_range_m + self.conduit_range_per_level_m * level,
+            charge_regen_per_second: self.base_charge_regen_per_second
+                + self.charge_regen_per_level * level,
+            max_health: self.base_max_health + self.max_health_per_level * level,
+        }
+    }
+
+    /// Cost to upgrade from `level`, or `None` if it's already maxed out.
+    pub fn upgrade_cost(&self, level: TowerLevel) -> Option<u32> {
+        (level.0 < self.max_level).then(|| self.upgrade_cost_per_level * (level.0 + 1))
+    }
+
+    /// Collider for a tower of `height`, offset so its base sits at the origin.
+    pub fn collider(&self, height: f32) -> Collider {
+        Collider::compound(vec![(
+            Vec3::Y * height / 2.0,
+            Quat::IDENTITY,
+            Collider::cylinder(self.radius, height),
+        )])
+    }
+}
+
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct TowerMaterialCache(Handle<StandardMaterial>);
+
+impl FromWorld for TowerMaterialCache {
+    fn from_world(world: &mut World) -> Self {
+        let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
+        Self(materials.add(StandardMaterial {
+            base_color: Color::from(GRAY),
+            perceptual_roughness: 1.0,
+            ..Default::default()
+        }))
+    }
+}
+
 #[auto_plugin(app=app)]
 pub(crate) fn plugin(app: &mut App) {
     app.add_observer(on_tower_added);
+    app.add_observer(on_tower_level_inserted);
+    app.add_observer(on_tower_died);
+    app.add_systems(
+        Update,
+        (place_tower_tops, recharge_sparks).in_set(PausableSystems),
+    );
 }
 
 fn on_tower_added(
     trigger: Trigger<OnAdd, Tower>,
     mut commands: Commands,
+    material_cache: Res<TowerMaterialCache>,
+    cfg: Res<TowerConfig>,
+) {
+    commands.entity(trigger.target()).insert((
+        MeshMaterial3d(material_cache.0.clone()),
+        GameLayer::tower(),
+        Health(cfg.stats(TowerLevel::default()).max_health),
+    ));
+}
+
+fn on_tower_level_inserted(
+    trigger: Trigger<OnInsert, TowerLevel>,
+    mut commands: Commands,
+    levels: Query<&TowerLevel>,
+    cfg: Res<TowerConfig>,
     mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
 ) {
-    const RADIUS: f32 = 10.0;
-    const HEIGHT: f32 = 100.0;
+    let level = levels.get(trigger.target()).expect("OnInsert broken");
+    let stats = cfg.stats(*level);
+    let mesh = Cylinder::new(cfg.radius, stats.height)
+        .mesh()
+        .build()
+        .translated_by(Vec3::Y * stats.height / 2.0);
     commands.entity(trigger.target()).insert((
-        Mesh3d(meshes.add(Cylinder::new(RADIUS, HEIGHT))),
-        MeshMaterial3d(materials.add(StandardMaterial {
-            base_color: Color::from(GRAY),
-            perceptual_roughness: 1.0,
-            ..Default::default()
-        })),
-        Collider::cylinder(RADIUS, HEIGHT),
+        stats,
+        MaxHealth(stats.max_health),
+        Mesh3d(meshes.add(mesh)),
+        cfg.collider(stats.height),
     ));
 }
+
+/// Crumbles, taking whatever's on top with it.
+fn on_tower_died(
+    trigger: Trigger<OnAdd, Dead>,
+    mut commands: Commands,
+    towers: Query<(), With<Tower>>,
+) {
+    if towers.contains(trigger.target()) {
+        commands.entity(trigger.target()).trigger(DespawnDelayed);
+    }
+}
+
+fn place_tower_tops(
+    towers: Query<(&TowerStats, &Children), Changed<TowerStats>>,
+    mut tops: Query<&mut Transform, With<TowerTop>>,
+) {
+    for (stats, children) in towers.iter() {
+        let mut iter = tops.iter_many_mut(children);
+        while let Some(mut transform) = iter.fetch_next() {
+            transform.translation.y = stats.height;
+        }
+    }
+}
+
+fn recharge_sparks(
+    towers: Query<(&GlobalTransform, &TowerStats), (With<Tower>, Without<Dead>)>,
+    sparks: Query<(Entity, &GlobalTransform), With<Spark>>,
+    time: Res<Time>,
+    mut adjust_hp_event: EventWriter<AdjustHp>,
+) {
+    for (tower_gt, stats) in towers.iter() {
+        for (spark, spark_gt) in sparks.iter() {
+            let dist = (spark_gt.translation() - tower_gt.translation()).length() * METERS_PER_UNIT;
+            if dist > stats.conduit_range_m {
+                continue;
+            }
+            adjust_hp_event.write(AdjustHp::new(
+                spark,
+                stats.charge_regen_per_second * time.delta_secs(),
+            ));
+        }
+    }
+}
@@ -1,4 +1,9 @@
+use crate::game::abilities::AbilityCaster;
 use crate::game::asset_tracking::LoadResource;
+use crate::game::physics::layers::GameLayer;
+use crate::game::prefabs::collider::PrefabCollider;
+use crate::game::prefabs::pending::PendingPrefabScene;
+use crate::game::prefabs::socket::AttachToSocket;
 use bevy::prelude::*;
 use bevy_auto_plugin::auto_plugin::*;
 
@@ -24,6 +29,33 @@ impl FromWorld for WizardAssets {
 #[reflect(Component)]
 pub struct Wizard;
 
+/// Nodes of the wizard model that attachments can be parented to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Reflect)]
+pub enum WizardSocket {
+    /// The staff's bone, where its conduit and spark anchor sit. It starts at
+    /// the grip, so attachments need an offset up to the tip.
+    StaffTip,
+    /// The free hand, for held items.
+    OffHand,
+    Head,
+}
+
+impl WizardSocket {
+    pub fn node_name(&self) -> &'static str {
+        match self {
+            Self::StaffTip => "DEF-STAFF",
+            Self::OffHand => "DEF-HAND.L",
+            Self::Head => "DEF-HEAD",
+        }
+    }
+}
+
+impl From<WizardSocket> for AttachToSocket {
+    fn from(socket: WizardSocket) -> Self {
+        AttachToSocket::new(socket.node_name())
+    }
+}
+
 #[auto_plugin(app=app)]
 pub(crate) fn plugin(app: &mut App) {
     app.load_resource::<WizardAssets>();
@@ -35,7 +67,10 @@ fn on_wizard_added(
     wizard: Res<WizardAssets>,
     mut commands: Commands,
 ) {
-    commands
-        .entity(trigger.target())
-        .insert(SceneRoot(wizard.wizard.clone()));
+    commands.entity(trigger.target()).insert((
+        PendingPrefabScene::scene(wizard.wizard.clone()),
+        PrefabCollider::ConvexHull,
+        GameLayer::tower(),
+        AbilityCaster::default(),
+    ));
 }
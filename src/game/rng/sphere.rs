@@ -1,6 +1,4 @@
-use crate::game::rng::Prng;
 use bevy::prelude::*;
-use bevy_rand::prelude::GlobalEntropy;
 use rand::Rng;
 use std::f32::consts::PI;
 
@@ -19,13 +17,3 @@ pub fn sample_point_on_sphere<R: Rng>(rng: &mut R) -> Vec3 {
 
     Vec3::new(x, y, z)
 }
-
-pub trait RandomSpherePoint {
-    fn random_sphere_point(&mut self, radius: f32) -> Vec3;
-}
-
-impl RandomSpherePoint for GlobalEntropy<'_, Prng> {
-    fn random_sphere_point(&mut self, radius: f32) -> Vec3 {
-        sample_point_on_sphere(self) * radius
-    }
-}
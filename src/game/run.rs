@@ -0,0 +1,123 @@
+//! A run from entering gameplay to losing the wizard's tower or clearing every
+//! wave, and the [`RunStats`] summed up at the end of it.
+
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+
+use crate::game::health::{Dead, HpAdjusted};
+use crate::game::pause_controller::PausableSystems;
+use crate::game::prefabs::enemy::Enemy;
+use crate::game::prefabs::tower::Tower;
+use crate::game::prefabs::wizard::Wizard;
+use crate::game::screens::Screen;
+use crate::game::spark::Zapping;
+use crate::game::waves::{WaveConfig, Waves};
+
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, Default, Copy, Clone, PartialEq, Reflect)]
+#[reflect(Resource)]
+pub struct RunStats {
+    pub time_survived_secs: f32,
+    pub enemies_killed: u32,
+    pub damage_dealt: f32,
+    /// Most targets a single spark zapped one after another.
+    pub longest_chain: u32,
+    pub spark_distance_m: f32,
+}
+
+/// Targets zapped so far by a spark.
+#[auto_register_type]
+#[derive(Component, Debug, Default, Copy, Clone, Reflect)]
+#[reflect(Component)]
+struct SparkChain(u32);
+
+#[auto_plugin(app=app)]
+pub(crate) fn plugin(app: &mut App) {
+    app.add_observer(count_kills);
+    app.add_observer(count_chain);
+    app.add_observer(lose_with_tower);
+    app.add_systems(OnEnter(Screen::Gameplay), reset_run_stats);
+    app.add_systems(
+        Update,
+        (tick_time_survived, count_damage_dealt, win_once_cleared)
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(PausableSystems),
+    );
+}
+
+fn reset_run_stats(mut stats: ResMut<RunStats>) {
+    *stats = RunStats::default();
+}
+
+fn tick_time_survived(time: Res<Time>, mut stats: ResMut<RunStats>) {
+    stats.time_survived_secs += time.delta_secs();
+}
+
+fn count_damage_dealt(
+    mut hp_adjusted_events: EventReader<HpAdjusted>,
+    enemies: Query<(), With<Enemy>>,
+    mut stats: ResMut<RunStats>,
+) {
+    stats.damage_dealt += hp_adjusted_events
+        .read()
+        .filter(|event| event.amount < 0.0 && enemies.contains(event.target))
+        .map(|event| -event.amount)
+        .sum::<f32>();
+}
+
+fn count_kills(
+    trigger: Trigger<OnAdd, Dead>,
+    enemies: Query<(), With<Enemy>>,
+    mut stats: ResMut<RunStats>,
+) {
+    if enemies.contains(trigger.target()) {
+        stats.enemies_killed += 1;
+    }
+}
+
+fn count_chain(
+    trigger: Trigger<OnInsert, Zapping>,
+    mut commands: Commands,
+    mut chains: Query<&mut SparkChain>,
+    mut stats: ResMut<RunStats>,
+) {
+    let length = match chains.get_mut(trigger.target()) {
+        Ok(mut chain) => {
+            chain.0 += 1;
+            chain.0
+        }
+        Err(_) => {
+            commands.entity(trigger.target()).insert(SparkChain(1));
+            1
+        }
+    };
+    stats.longest_chain = stats.longest_chain.max(length);
+}
+
+/// The run is lost along with the tower the wizard stands on.
+fn lose_with_tower(
+    trigger: Trigger<OnAdd, Dead>,
+    towers: Query<(), With<Tower>>,
+    children: Query<&Children>,
+    wizards: Query<(), With<Wizard>>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    if towers.contains(trigger.target())
+        && children
+            .iter_descendants(trigger.target())
+            .any(|descendant| wizards.contains(descendant))
+    {
+        next_screen.set(Screen::GameOver);
+    }
+}
+
+fn win_once_cleared(
+    cfg: Res<WaveConfig>,
+    waves: Res<Waves>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    if waves.all_cleared(&cfg) {
+        next_screen.set(Screen::Victory);
+    }
+}
@@ -1,11 +1,18 @@
+use crate::game::abilities::CastOrigin;
 use crate::game::behaviors::target_ent::TargetEnt;
 use crate::game::camera::CameraTarget;
+use crate::game::camera::bounds::CameraBounds;
 use crate::game::effects::lightning_ball::{LightningBall, LightningBallConduit};
+use crate::game::health::{Health, MaxHealth};
+use crate::game::physics::layers::GameLayer;
 use crate::game::prefabs::enemy::Enemy;
-use crate::game::prefabs::tower::Tower;
-use crate::game::prefabs::wizard::Wizard;
+use crate::game::prefabs::socket::AttachToSocket;
+use crate::game::prefabs::tower::{Tower, TowerTop};
+use crate::game::prefabs::wizard::{Wizard, WizardSocket};
 use crate::game::screens::Screen;
-use avian3d::prelude::Collider;
+use crate::game::spark::SparkAnchor;
+use crate::game::steering::Steering;
+use avian3d::prelude::{Collider, RigidBody};
 use bevy::color::palettes::css::GREEN;
 use bevy::prelude::*;
 use bevy_auto_plugin::auto_plugin::*;
@@ -22,6 +29,15 @@ pub fn spawn_level(
         .spawn((
             Name::new("Level"),
             StateScoped(Screen::Gameplay),
+            // Over the grass and never underneath it, whose top is at 5.0
+            CameraBounds {
+                min_focus: Vec3::new(-500.0, 5.0, -500.0),
+                max_focus: Vec3::new(500.0, 400.0, 500.0),
+                min_radius: 50.0,
+                max_radius: 1500.0,
+                min_pitch: 0.05,
+                max_pitch: 1.5,
+            },
             Transform::default(),
             Visibility::default(),
             children![
@@ -34,10 +50,17 @@ pub fn spawn_level(
                         reflectance: 0.0,
                         ..Default::default()
                     })),
+                    RigidBody::Static,
+                    Collider::cuboid(1000.0, 10.0, 1000.0),
+                    GameLayer::terrain(),
                 ),
                 (
                     LightningBall,
-                    CameraTarget,
+                    CameraTarget::default(),
+                    Steering::default(),
+                    // Charge for the ball to move on
+                    Health(100.0),
+                    MaxHealth(100.0),
                     Transform::from_xyz(0.0, 3.1 * 10.0 + 100.0, 0.8 * 10.0),
                 ),
             ],
@@ -48,14 +71,20 @@ pub fn spawn_level(
         .entity(level_ent)
         .with_child((
             Tower,
-            Transform::from_xyz(0.0, 50.0, 0.0),
+            Transform::default(),
             children![(
                 Wizard,
-                Transform::from_xyz(0.0, 50.0, 0.0).with_scale(Vec3::splat(10.0)),
+                TowerTop,
+                Transform::from_scale(Vec3::splat(10.0)),
                 children![(
-                    Name::new("Fake Staff Pos"),
+                    Name::new("Staff Conduit"),
                     LightningBallConduit,
-                    Transform::from_xyz(-0.81, 1.95, -0.09),
+                    SparkAnchor,
+                    CastOrigin,
+                    AttachToSocket::from(WizardSocket::StaffTip),
+                    // The staff bone starts at the grip and is scaled down to
+                    // about a fifth, so move up to the tip and undo the scale
+                    Transform::from_xyz(0.0, 2.75, 0.0).with_scale(Vec3::splat(5.0)),
                     Collider::sphere(0.25)
                 )],
             ),],
@@ -4,6 +4,7 @@ use crate::game::menus::Menu;
 use crate::game::pause_controller::Pause;
 use crate::game::scenes::game::spawn_level;
 use crate::game::screens::Screen;
+use crate::game::settings::binding_just_pressed;
 use bevy::{input::common_conditions::input_just_pressed, prelude::*, ui::Val::*};
 use bevy_auto_plugin::auto_plugin::*;
 
@@ -54,14 +55,14 @@ pub(super) fn plugin(app: &mut App) {
         Update,
         (
             (pause, spawn_pause_overlay, open_pause_menu).run_if(
-                in_state(Screen::Gameplay)
-                    .and(in_state(Menu::None))
-                    .and(input_just_pressed(KeyCode::KeyP).or(input_just_pressed(KeyCode::Escape))),
+                in_state(Screen::Gameplay).and(in_state(Menu::None)).and(
+                    binding_just_pressed(|keys| keys.pause).or(input_just_pressed(KeyCode::Escape)),
+                ),
             ),
             close_menu.run_if(
                 in_state(Screen::Gameplay)
                     .and(not(in_state(Menu::None)))
-                    .and(input_just_pressed(KeyCode::KeyP)),
+                    .and(binding_just_pressed(|keys| keys.pause)),
             ),
         ),
     );
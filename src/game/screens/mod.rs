@@ -2,6 +2,7 @@
 
 mod gameplay;
 mod loading;
+mod run_end;
 mod splash;
 mod title;
 
@@ -19,6 +20,8 @@ pub enum Screen {
     Title,
     Loading,
     Gameplay,
+    GameOver,
+    Victory,
 }
 
 #[auto_plugin(app=app)]
@@ -26,6 +29,7 @@ pub(super) fn plugin(app: &mut App) {
     app.add_plugins((
         gameplay::plugin,
         loading::plugin,
+        run_end::plugin,
         splash::plugin,
         title::plugin,
     ));
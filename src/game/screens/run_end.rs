@@ -0,0 +1,69 @@
+//! The screens shown once a run is lost or won, summing it up.
+
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+
+use crate::game::{run::RunStats, screens::Screen, theme::widget};
+
+const GAME_OVER_BACKGROUND_COLOR: Color = Color::srgb(0.2, 0.05, 0.05);
+const VICTORY_BACKGROUND_COLOR: Color = Color::srgb(0.05, 0.15, 0.2);
+
+fn spawn_game_over_screen(mut commands: Commands, stats: Res<RunStats>) {
+    commands.spawn(run_end_screen("Game Over", Screen::GameOver, &stats));
+}
+
+fn spawn_victory_screen(mut commands: Commands, stats: Res<RunStats>) {
+    commands.spawn(run_end_screen("Victory!", Screen::Victory, &stats));
+}
+
+fn run_end_screen(title: &'static str, screen: Screen, stats: &RunStats) -> impl Bundle {
+    let survived = stats.time_survived_secs as u32;
+    (
+        widget::ui_root(title),
+        StateScoped(screen),
+        children![
+            widget::header(title),
+            widget::label(format!(
+                "Time survived: {}:{:02}",
+                survived / 60,
+                survived % 60
+            )),
+            widget::label(format!("Enemies killed: {}", stats.enemies_killed)),
+            widget::label(format!("Damage dealt: {:.0}", stats.damage_dealt)),
+            widget::label(format!("Longest chain: {}", stats.longest_chain)),
+            widget::label(format!("Spark distance: {:.0} m", stats.spark_distance_m)),
+            widget::button("Retry", retry),
+            widget::button("Main Menu", quit_to_title),
+        ],
+    )
+}
+
+fn retry(_: Trigger<Pointer<Click>>, mut next_screen: ResMut<NextState<Screen>>) {
+    next_screen.set(Screen::Gameplay);
+}
+
+fn quit_to_title(_: Trigger<Pointer<Click>>, mut next_screen: ResMut<NextState<Screen>>) {
+    next_screen.set(Screen::Title);
+}
+
+#[auto_plugin(app=app)]
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        OnEnter(Screen::GameOver),
+        (
+            |mut clear_color: ResMut<ClearColor>| {
+                *clear_color = ClearColor(GAME_OVER_BACKGROUND_COLOR);
+            },
+            spawn_game_over_screen,
+        ),
+    );
+    app.add_systems(
+        OnEnter(Screen::Victory),
+        (
+            |mut clear_color: ResMut<ClearColor>| {
+                *clear_color = ClearColor(VICTORY_BACKGROUND_COLOR);
+            },
+            spawn_victory_screen,
+        ),
+    );
+}
@@ -0,0 +1,405 @@
+//! User options shared by whichever systems they affect, changed from the
+//! settings menu. They're loaded from the [`SettingsStore`] before anything
+//! starts up and saved back to it whenever one changes.
+
+mod storage;
+
+use bevy::core_pipeline::bloom::Bloom;
+use bevy::prelude::*;
+use bevy::window::{PresentMode, PrimaryWindow};
+use bevy_auto_plugin::auto_plugin::*;
+use serde::{Deserialize, Serialize};
+use smart_default::SmartDefault;
+
+use crate::game::camera::MainCamera;
+#[cfg(not(target_family = "wasm"))]
+pub use storage::FileStorage;
+pub use storage::{MemoryStorage, SettingsStorage};
+
+/// Loudest the master volume goes, as a linear multiplier.
+pub const MAX_VOLUME: f32 = 3.0;
+
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(
+    Resource, Debug, Copy, Clone, PartialEq, Reflect, SmartDefault, Serialize, Deserialize,
+)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct Accessibility {
+    /// Tones down flickering lights and flashes.
+    pub reduced_flashing: bool,
+    /// How much the camera shakes, from `0.0` for not at all to `1.0`.
+    #[default(1.0)]
+    pub camera_shake: f32,
+}
+
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(
+    Resource, Debug, Copy, Clone, PartialEq, Reflect, SmartDefault, Serialize, Deserialize,
+)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct Graphics {
+    #[default(true)]
+    pub vsync: bool,
+    #[default(true)]
+    pub bloom: bool,
+}
+
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(
+    Resource, Debug, Copy, Clone, PartialEq, Reflect, SmartDefault, Serialize, Deserialize,
+)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct KeyBindings {
+    #[default(KeyCode::KeyW)]
+    pub forward: KeyCode,
+    #[default(KeyCode::KeyS)]
+    pub back: KeyCode,
+    #[default(KeyCode::KeyA)]
+    pub left: KeyCode,
+    #[default(KeyCode::KeyD)]
+    pub right: KeyCode,
+    #[default(KeyCode::Space)]
+    pub ascend: KeyCode,
+    #[default(KeyCode::ShiftLeft)]
+    pub descend: KeyCode,
+    #[default(KeyCode::KeyC)]
+    pub camera_mode: KeyCode,
+    #[default(KeyCode::KeyB)]
+    pub build_mode: KeyCode,
+    #[default(KeyCode::KeyP)]
+    pub pause: KeyCode,
+}
+
+/// Run condition for the key bound by `binding` having just been pressed, like
+/// [`input_just_pressed`](bevy::input::common_conditions::input_just_pressed).
+pub fn binding_just_pressed(
+    binding: fn(&KeyBindings) -> KeyCode,
+) -> impl FnMut(Res<ButtonInput<KeyCode>>, Res<KeyBindings>) -> bool + Clone {
+    move |keys, bindings| keys.just_pressed(binding(&bindings))
+}
+
+/// Short name for `key` to show the player, e.g. `B` rather than `KeyB`.
+pub fn key_name(key: KeyCode) -> String {
+    let name = format!("{key:?}");
+    name.strip_prefix("Key")
+        .or_else(|| name.strip_prefix("Digit"))
+        .unwrap_or(&name)
+        .to_string()
+}
+
+/// Where the settings are loaded from and saved to.
+#[derive(Resource)]
+pub struct SettingsStore {
+    storage: Box<dyn SettingsStorage>,
+    /// What was last loaded or saved, so it isn't written again.
+    last_saved: Option<String>,
+}
+
+impl SettingsStore {
+    pub fn new(storage: impl SettingsStorage) -> Self {
+        Self {
+            storage: Box::new(storage),
+            last_saved: None,
+        }
+    }
+}
+
+impl Default for SettingsStore {
+    fn default() -> Self {
+        #[cfg(not(target_family = "wasm"))]
+        if let Some(storage) = FileStorage::in_config_dir() {
+            return Self::new(storage);
+        }
+        Self::new(MemoryStorage::default())
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize, SmartDefault)]
+#[serde(default)]
+struct Volumes {
+    /// Linear, from `0.0` to [`MAX_VOLUME`].
+    #[default(1.0)]
+    master: f32,
+}
+
+/// Everything saved, as written to the [`SettingsStore`].
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize, SmartDefault)]
+#[serde(default)]
+struct SettingsFile {
+    #[default(SettingsFile::VERSION)]
+    version: u32,
+    volume: Volumes,
+    graphics: Graphics,
+    accessibility: Accessibility,
+    key_bindings: KeyBindings,
+}
+
+impl SettingsFile {
+    const VERSION: u32 = 1;
+
+    /// Reads a file saved by any version of the game. Fields added since are
+    /// filled in with their defaults and fields that have gone are skipped, so
+    /// only a change to what a field means needs migrating here.
+    fn parse(contents: &str) -> Result<Self, ron::error::SpannedError> {
+        let file: Self = ron::from_str(contents)?;
+        match file.version {
+            // Older versions get an arm here bringing them up to date one
+            // version at a time, once there are any
+            Self::VERSION => {}
+            newer if newer > Self::VERSION => warn!(
+                "settings were saved by a newer version ({newer} > {}), keeping what's understood",
+                Self::VERSION
+            ),
+            unknown => warn!(
+                "settings have an unknown version {unknown}, reading them as {}",
+                Self::VERSION
+            ),
+        }
+        Ok(Self {
+            version: Self::VERSION,
+            ..file
+        }
+        .sanitized())
+    }
+
+    fn to_ron(self) -> Result<String, ron::Error> {
+        ron::ser::to_string_pretty(&self, ron::ser::PrettyConfig::default())
+    }
+
+    /// Values edited out of range are pulled back in.
+    fn sanitized(mut self) -> Self {
+        let defaults = Self::default();
+        let finite_or = |value: f32, default: f32| {
+            if value.is_finite() { value } else { default }
+        };
+        self.volume.master =
+            finite_or(self.volume.master, defaults.volume.master).clamp(0.0, MAX_VOLUME);
+        self.accessibility.camera_shake = finite_or(
+            self.accessibility.camera_shake,
+            defaults.accessibility.camera_shake,
+        )
+        .clamp(0.0, 1.0);
+        self
+    }
+}
+
+#[auto_plugin(app=app)]
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<SettingsStore>();
+    app.init_resource::<GlobalVolume>();
+    app.add_systems(PreStartup, load_settings);
+    app.add_systems(Update, apply_graphics.run_if(resource_changed::<Graphics>));
+    app.add_systems(
+        Last,
+        save_settings.run_if(
+            resource_changed::<GlobalVolume>
+                .or(resource_changed::<Graphics>)
+                .or(resource_changed::<Accessibility>)
+                .or(resource_changed::<KeyBindings>),
+        ),
+    );
+}
+
+fn load_settings(
+    mut store: ResMut<SettingsStore>,
+    mut global_volume: ResMut<GlobalVolume>,
+    mut graphics: ResMut<Graphics>,
+    mut accessibility: ResMut<Accessibility>,
+    mut key_bindings: ResMut<KeyBindings>,
+) {
+    let file = match store.storage.read() {
+        Ok(Some(contents)) => SettingsFile::parse(&contents).unwrap_or_else(|err| {
+            warn!(
+                "ignoring unreadable settings in {}: {err}",
+                store.storage.describe()
+            );
+            SettingsFile::default()
+        }),
+        Ok(None) => SettingsFile::default(),
+        Err(err) => {
+            warn!(
+                "couldn't load settings from {}: {err}",
+                store.storage.describe()
+            );
+            SettingsFile::default()
+        }
+    };
+
+    global_volume.volume = bevy::audio::Volume::Linear(file.volume.master);
+    *graphics = file.graphics;
+    *accessibility = file.accessibility;
+    *key_bindings = file.key_bindings;
+    // Nothing's written until something changes, so a file that couldn't be
+    // read is left alone until then
+    store.last_saved = file.to_ron().ok();
+}
+
+fn save_settings(
+    mut store: ResMut<SettingsStore>,
+    global_volume: Res<GlobalVolume>,
+    graphics: Res<Graphics>,
+    accessibility: Res<Accessibility>,
+    key_bindings: Res<KeyBindings>,
+) {
+    let file = SettingsFile {
+        version: SettingsFile::VERSION,
+        volume: Volumes {
+            master: global_volume.volume.to_linear(),
+        },
+        graphics: *graphics,
+        accessibility: *accessibility,
+        key_bindings: *key_bindings,
+    };
+    let contents = match file.to_ron() {
+        Ok(contents) => contents,
+        Err(err) => {
+            warn!("couldn't serialize settings: {err}");
+            return;
+        }
+    };
+    if store.last_saved.as_ref() == Some(&contents) {
+        return;
+    }
+    if let Err(err) = store.storage.write(&contents) {
+        warn!(
+            "couldn't save settings to {}: {err}",
+            store.storage.describe()
+        );
+        return;
+    }
+    store.last_saved = Some(contents);
+}
+
+fn apply_graphics(
+    mut commands: Commands,
+    graphics: Res<Graphics>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+    cameras: Query<Entity, With<MainCamera>>,
+) {
+    let present_mode = if graphics.vsync {
+        PresentMode::AutoVsync
+    } else {
+        PresentMode::AutoNoVsync
+    };
+    for mut window in windows.iter_mut() {
+        if window.present_mode != present_mode {
+            window.present_mode = present_mode;
+        }
+    }
+    for camera in cameras.iter() {
+        if graphics.bloom {
+            commands.entity(camera).insert(Bloom::NATURAL);
+        } else {
+            commands.entity(camera).remove::<Bloom>();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::audio::Volume;
+
+    fn test_app(storage: MemoryStorage) -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.insert_resource(SettingsStore::new(storage));
+        app.add_plugins(plugin);
+        app
+    }
+
+    #[test]
+    fn loads_saved_settings() {
+        let storage = MemoryStorage::with_contents(
+            "(version: 1, volume: (master: 0.5), accessibility: (reduced_flashing: true), \
+             key_bindings: (pause: Escape))",
+        );
+        let mut app = test_app(storage);
+        app.update();
+
+        let world = app.world();
+        assert_eq!(world.resource::<GlobalVolume>().volume.to_linear(), 0.5);
+        assert!(world.resource::<Accessibility>().reduced_flashing);
+        assert_eq!(world.resource::<KeyBindings>().pause, KeyCode::Escape);
+        // Everything left out keeps its default
+        assert_eq!(world.resource::<Accessibility>().camera_shake, 1.0);
+        assert_eq!(world.resource::<KeyBindings>().forward, KeyCode::KeyW);
+        assert_eq!(*world.resource::<Graphics>(), Graphics::default());
+    }
+
+    #[test]
+    fn saves_changed_settings() {
+        let storage = MemoryStorage::default();
+        let mut app = test_app(storage.clone());
+        app.update();
+        assert_eq!(storage.contents(), None, "defaults aren't saved unchanged");
+
+        app.world_mut().resource_mut::<Accessibility>().camera_shake = 0.3;
+        app.world_mut().resource_mut::<GlobalVolume>().volume = Volume::Linear(2.0);
+        app.update();
+
+        let saved = SettingsFile::parse(&storage.contents().expect("saved")).expect("readable");
+        assert_eq!(saved.accessibility.camera_shake, 0.3);
+        assert_eq!(saved.volume.master, 2.0);
+    }
+
+    #[test]
+    fn ignores_corrupted_settings() {
+        let corrupted = "(version: 1, accessibility: (camera_shake: ";
+        let storage = MemoryStorage::with_contents(corrupted);
+        let mut app = test_app(storage.clone());
+        app.update();
+        app.update();
+
+        assert_eq!(
+            *app.world().resource::<Accessibility>(),
+            Accessibility::default()
+        );
+        assert_eq!(storage.contents().as_deref(), Some(corrupted));
+    }
+
+    #[test]
+    fn reads_settings_from_other_versions() {
+        let file = SettingsFile::parse(
+            "(version: 99, accessibility: (camera_shake: 0.5), new_section: (thing: Some(3)))",
+        )
+        .expect("readable");
+        assert_eq!(file.version, SettingsFile::VERSION);
+        assert_eq!(file.accessibility.camera_shake, 0.5);
+
+        let unversioned = SettingsFile::parse("(graphics: (vsync: false))").expect("readable");
+        assert!(!unversioned.graphics.vsync);
+    }
+
+    #[test]
+    fn pulls_values_back_in_range() {
+        let file =
+            SettingsFile::parse("(volume: (master: 10.0), accessibility: (camera_shake: NaN))")
+                .expect("readable");
+        assert_eq!(file.volume.master, MAX_VOLUME);
+        assert_eq!(file.accessibility.camera_shake, 1.0);
+    }
+
+    #[test]
+    fn round_trips() {
+        let file = SettingsFile {
+            graphics: Graphics {
+                vsync: false,
+                bloom: false,
+            },
+            key_bindings: KeyBindings {
+                ascend: KeyCode::KeyE,
+                ..default()
+            },
+            ..default()
+        };
+        let contents = file.to_ron().expect("serializable");
+        assert_eq!(SettingsFile::parse(&contents).expect("readable"), file);
+    }
+}
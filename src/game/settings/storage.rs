@@ -0,0 +1,124 @@
+//! Where the settings file lives. Native builds keep it in the platform's
+//! config directory, anything else only for as long as the game runs.
+
+use std::io;
+use std::sync::{Arc, Mutex};
+
+/// Reads and writes the settings file as a whole.
+pub trait SettingsStorage: Send + Sync + 'static {
+    /// `None` if nothing has been saved yet.
+    fn read(&self) -> io::Result<Option<String>>;
+    fn write(&self, contents: &str) -> io::Result<()>;
+    /// Where the settings are kept, for logs.
+    fn describe(&self) -> String;
+}
+
+/// Keeps the settings in memory. Clones share the same contents, so tests can
+/// hold on to one to see what was saved.
+#[derive(Debug, Default, Clone)]
+pub struct MemoryStorage(Arc<Mutex<Option<String>>>);
+
+impl MemoryStorage {
+    #[cfg(test)]
+    pub fn with_contents(contents: impl Into<String>) -> Self {
+        Self(Arc::new(Mutex::new(Some(contents.into()))))
+    }
+
+    pub fn contents(&self) -> Option<String> {
+        self.0.lock().ok().and_then(|contents| contents.clone())
+    }
+}
+
+impl SettingsStorage for MemoryStorage {
+    fn read(&self) -> io::Result<Option<String>> {
+        Ok(self.contents())
+    }
+
+    fn write(&self, contents: &str) -> io::Result<()> {
+        let mut stored = self
+            .0
+            .lock()
+            .map_err(|_| io::Error::other("settings lock poisoned"))?;
+        *stored = Some(contents.to_string());
+        Ok(())
+    }
+
+    fn describe(&self) -> String {
+        "memory".to_string()
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+pub use file::FileStorage;
+
+#[cfg(not(target_family = "wasm"))]
+mod file {
+    use std::path::PathBuf;
+    use std::{fs, io};
+
+    use super::SettingsStorage;
+
+    const APP_DIR: &str = "bevy_game_jam_6";
+    const FILE_NAME: &str = "settings.ron";
+
+    /// Keeps the settings in a file.
+    #[derive(Debug, Clone)]
+    pub struct FileStorage {
+        path: PathBuf,
+    }
+
+    impl FileStorage {
+        /// `settings.ron` in the platform's config directory, if it has one.
+        pub fn in_config_dir() -> Option<Self> {
+            Some(Self {
+                path: config_dir()?.join(APP_DIR).join(FILE_NAME),
+            })
+        }
+    }
+
+    impl SettingsStorage for FileStorage {
+        fn read(&self) -> io::Result<Option<String>> {
+            match fs::read_to_string(&self.path) {
+                Ok(contents) => Ok(Some(contents)),
+                Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+                Err(err) => Err(err),
+            }
+        }
+
+        fn write(&self, contents: &str) -> io::Result<()> {
+            if let Some(dir) = self.path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            // Written alongside and moved into place, so a crash halfway
+            // through can't leave half a file behind
+            let temp = self.path.with_extension("ron.tmp");
+            fs::write(&temp, contents)?;
+            fs::rename(&temp, &self.path)
+        }
+
+        fn describe(&self) -> String {
+            self.path.display().to_string()
+        }
+    }
+
+    fn env_dir(key: &str) -> Option<PathBuf> {
+        std::env::var_os(key)
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from)
+    }
+
+    #[cfg(target_os = "windows")]
+    fn config_dir() -> Option<PathBuf> {
+        env_dir("APPDATA")
+    }
+
+    #[cfg(target_os = "macos")]
+    fn config_dir() -> Option<PathBuf> {
+        Some(env_dir("HOME")?.join("Library/Application Support"))
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    fn config_dir() -> Option<PathBuf> {
+        env_dir("XDG_CONFIG_HOME").or_else(|| Some(env_dir("HOME")?.join(".config")))
+    }
+}
@@ -19,6 +19,14 @@ pub struct SparkConfig {
     pub damage_dealt_per_second: f32,
     #[default(50.0)]
     pub max_distance_jump_m: f32,
+    /// Impulse given to whatever a spark jumps to.
+    #[default(5000.0)]
+    pub jump_knockback: f32,
+    /// Impulse given to everything around a spark when it discharges.
+    #[default(4000.0)]
+    pub discharge_knockback: f32,
+    #[default(15.0)]
+    pub discharge_radius_m: f32,
 }
 
 #[auto_plugin(app=app)]
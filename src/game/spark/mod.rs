@@ -5,11 +5,14 @@ mod config;
 use bevy::prelude::*;
 use bevy_auto_plugin::auto_plugin::*;
 
-use crate::game::{constants::METERS_PER_UNIT, despawn::DespawnDelayed, health::Dead};
+use crate::game::{
+    constants::METERS_PER_UNIT, despawn::DespawnDelayed, health::Dead, run::RunStats,
+};
 
 use super::{
     health::{AdjustHp, Health, MaxHealth},
     pause_controller::PausableSystems,
+    physics::knockback::{Knockback, KnockbackVelocity},
     snapshot::Snapshot,
 };
 
@@ -26,6 +29,14 @@ pub struct Spark;
 #[require(Transform, Pickable)]
 pub struct SparkTarget;
 
+/// Where a [`Spark`] attaches when zapping the [`SparkTarget`] this is a
+/// descendant of, e.g. a socket on the target's model. Without one, sparks
+/// attach to the target itself.
+#[auto_register_type]
+#[derive(Component, Reflect)]
+#[require(Transform)]
+pub struct SparkAnchor;
+
 /// Spark -> Zapping -> SparkTarget
 /// Inserts ChildOf
 #[auto_register_type]
@@ -72,6 +83,7 @@ impl SparkTarget {
             sparks: Query<(Entity, &GlobalTransform), With<Spark>>,
             targets: Query<&GlobalTransform, With<SparkTarget>>,
             cfg: Res<SparkConfig>,
+            mut knockback_event: EventWriter<Knockback>,
         ) {
             let tl_target = targets
                 .get(tr.target())
@@ -88,6 +100,11 @@ impl SparkTarget {
                     .entity(spark)
                     .remove::<Zapping>()
                     .insert(Zapping(tr.target()));
+                knockback_event.write(Knockback::new(
+                    tr.target(),
+                    tl_target - tf_spark.translation(),
+                    cfg.jump_knockback,
+                ));
             }
         }
 
@@ -100,12 +117,18 @@ impl Zapping {
     fn handle_inserted(
         tr: Trigger<OnInsert, Self>,
         comp: Query<&Self, Added<Self>>,
+        children: Query<&Children>,
+        anchors: Query<(), With<SparkAnchor>>,
         mut commands: Commands,
     ) {
         let comp = comp.get(tr.target()).expect("OnInsert broken");
+        let anchor = children
+            .iter_descendants(comp.0)
+            .find(|&descendant| anchors.contains(descendant))
+            .unwrap_or(comp.0);
         commands
             .entity(tr.target())
-            .insert((ChildOf(comp.0), Transform::default())); // TODO better relative positioning
+            .insert((ChildOf(anchor), Transform::default()));
     }
 
     fn handle_removed(
@@ -132,7 +155,23 @@ impl ZappedBy {
 
 impl Spark {
     fn handle_inserted(tr: Trigger<OnInsert, Self>, mut commands: Commands, cfg: Res<SparkConfig>) {
-        fn handle_death(tr: Trigger<OnInsert, Dead>, mut commands: Commands) {
+        /// Discharges, pushing away everything around it
+        fn handle_death(
+            tr: Trigger<OnAdd, Dead>,
+            mut commands: Commands,
+            sparks: Query<&GlobalTransform>,
+            pushable: Query<(Entity, &GlobalTransform), With<KnockbackVelocity>>,
+            mut knockback_event: EventWriter<Knockback>,
+            cfg: Res<SparkConfig>,
+        ) {
+            if let Ok(spark_gt) = sparks.get(tr.target()) {
+                let center = spark_gt.translation();
+                knockback_event.write_batch(pushable.iter().filter_map(|(entity, gt)| {
+                    let offset = gt.translation() - center;
+                    (offset.length() * METERS_PER_UNIT <= cfg.discharge_radius_m)
+                        .then(|| Knockback::new(entity, offset, cfg.discharge_knockback))
+                }));
+            }
             commands.entity(tr.target()).trigger(DespawnDelayed);
         }
 
@@ -163,18 +202,20 @@ mod spark {
     }
 
     pub fn deal_dot(
-        targets: Query<Entity, (With<ZappedBy>, With<Health>, Without<Dead>)>,
+        targets: Query<(Entity, &ZappedBy), (With<Health>, Without<Dead>)>,
         time: Res<Time>,
         mut adjust_hp_event: EventWriter<AdjustHp>,
         cfg: Res<SparkConfig>,
     ) {
         let damage_amount = time.delta_secs() * cfg.damage_dealt_per_second;
 
-        adjust_hp_event.write_batch(
-            targets
-                .iter()
-                .map(|target| AdjustHp::new(target, -damage_amount)),
-        );
+        adjust_hp_event.write_batch(targets.iter().map(|(target, zapped_by)| {
+            let damage = AdjustHp::new(target, -damage_amount);
+            match zapped_by.iter().next() {
+                Some(spark) => damage.with_source(spark),
+                None => damage,
+            }
+        }));
     }
 
     pub fn apply_distance_cost(
@@ -183,6 +224,7 @@ mod spark {
             (With<Spark>, Changed<GlobalTransform>),
         >,
         mut adjust_hp_event: EventWriter<AdjustHp>,
+        mut run_stats: ResMut<RunStats>,
         cfg: Res<SparkConfig>,
     ) {
         for (spark, gt_new, mut gt_snap) in sparks.iter_mut() {
@@ -192,6 +234,7 @@ mod spark {
 
             let dist = (gt_new.translation() - gt_old.translation()).length() * METERS_PER_UNIT;
 
+            run_stats.spark_distance_m += dist;
             adjust_hp_event.write(AdjustHp::new(spark, -dist * cfg.cost_per_m));
         }
     }
@@ -0,0 +1,212 @@
+//! Player steering. An entity with [`Steering`] flies around from WASD (or its
+//! [`KeyBindings`]) or the left stick of a gamepad relative to the
+//! [`MainCamera`], with Space/Shift or the triggers to climb and descend. It
+//! speeds up and slows down through acceleration and drag, stays between its
+//! height limits and slides along the level rather than going through it.
+//! Moving spends the charge it runs on, taken from whatever it's [`ChargedBy`]
+//! or its own [`Health`].
+
+use avian3d::prelude::{Collider, RigidBody, SpatialQuery};
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+use smart_default::SmartDefault;
+
+use crate::game::camera::MainCamera;
+use crate::game::effects::lightning_ball::ChargedBy;
+use crate::game::health::{AdjustHp, Dead, Health};
+use crate::game::pause_controller::PausableSystems;
+use crate::game::physics::character::collide_and_slide;
+use crate::game::settings::KeyBindings;
+
+#[auto_register_type]
+#[derive(Component, Debug, Clone, Reflect, SmartDefault)]
+#[reflect(Component)]
+#[require(SteeringVelocity, RigidBody = RigidBody::Kinematic)]
+pub struct Steering {
+    #[default(150.0)]
+    pub max_speed: f32,
+    #[default(100.0)]
+    pub max_vertical_speed: f32,
+    #[default(400.0)]
+    pub acceleration: f32,
+    /// How quickly it slows down without input, per second.
+    #[default(2.0)]
+    pub drag: f32,
+    #[default(20.0)]
+    pub min_height: f32,
+    #[default(300.0)]
+    pub max_height: f32,
+    /// Distance kept from the level when sliding along it.
+    #[default(0.5)]
+    pub skin: f32,
+    #[default(4)]
+    pub max_slides: usize,
+    /// Charge spent per unit travelled.
+    #[default(0.02)]
+    pub charge_per_unit: f32,
+}
+
+#[auto_register_type]
+#[derive(Component, Debug, Default, Copy, Clone, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct SteeringVelocity(pub Vec3);
+
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, Clone, Reflect, SmartDefault)]
+#[reflect(Resource)]
+pub struct SteeringConfig {
+    /// Stick input below this is ignored.
+    #[default(0.15)]
+    pub gamepad_deadzone: f32,
+}
+
+#[auto_plugin(app=app)]
+pub(crate) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (accelerate, move_steered).chain().in_set(PausableSystems),
+    );
+}
+
+/// Direction the player is pushing in, relative to the camera, up to unit
+/// length horizontally and vertically.
+fn steering_input(
+    keys: &ButtonInput<KeyCode>,
+    bindings: &KeyBindings,
+    gamepads: &Query<&Gamepad>,
+    deadzone: f32,
+) -> (Vec2, f32) {
+    let mut planar = Vec2::ZERO;
+    let mut vertical = 0.0;
+    for (key, direction) in [
+        (bindings.forward, Vec2::Y),
+        (bindings.back, Vec2::NEG_Y),
+        (bindings.left, Vec2::NEG_X),
+        (bindings.right, Vec2::X),
+    ] {
+        if keys.pressed(key) {
+            planar += direction;
+        }
+    }
+    if keys.pressed(bindings.ascend) {
+        vertical += 1.0;
+    }
+    if keys.pressed(bindings.descend) {
+        vertical -= 1.0;
+    }
+    for gamepad in gamepads.iter() {
+        let stick = gamepad.left_stick();
+        if stick.length() > deadzone {
+            planar += stick;
+        }
+        vertical += gamepad
+            .get(GamepadButton::RightTrigger2)
+            .unwrap_or_default();
+        vertical -= gamepad.get(GamepadButton::LeftTrigger2).unwrap_or_default();
+    }
+    (planar.clamp_length_max(1.0), vertical.clamp(-1.0, 1.0))
+}
+
+fn accelerate(
+    time: Res<Time>,
+    keys: Res<ButtonInput<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    gamepads: Query<&Gamepad>,
+    cfg: Res<SteeringConfig>,
+    camera: Option<Single<&GlobalTransform, With<MainCamera>>>,
+    mut steered: Query<(&Steering, &mut SteeringVelocity), Without<Dead>>,
+) {
+    let dt = time.delta_secs();
+    let (planar, vertical) = steering_input(&keys, &key_bindings, &gamepads, cfg.gamepad_deadzone);
+    // Forward is wherever the camera looks, flattened onto the ground
+    let (forward, right) = camera.map_or((Vec3::NEG_Z, Vec3::X), |camera| {
+        let forward = camera.forward().with_y(0.0).normalize_or(Vec3::NEG_Z);
+        (forward, forward.cross(Vec3::Y))
+    });
+    let wish = (forward * planar.y + right * planar.x).with_y(0.0);
+
+    for (steering, mut velocity) in steered.iter_mut() {
+        let target = wish * steering.max_speed + Vec3::Y * vertical * steering.max_vertical_speed;
+        let mut next = velocity.0 * (-steering.drag * dt).exp();
+        if target != Vec3::ZERO {
+            let towards = target - next;
+            next += towards.clamp_length_max(steering.acceleration * dt);
+        }
+        if next.length_squared() < 1e-4 {
+            next = Vec3::ZERO;
+        }
+        velocity.set_if_neq(SteeringVelocity(next));
+    }
+}
+
+fn move_steered(
+    time: Res<Time>,
+    spatial_query: SpatialQuery,
+    mut steered: Query<
+        (
+            Entity,
+            &Steering,
+            &mut SteeringVelocity,
+            &mut Transform,
+            &Collider,
+            Option<&ChargedBy>,
+        ),
+        Without<Dead>,
+    >,
+    has_health: Query<(), (With<Health>, Without<Dead>)>,
+    mut adjust_hp_events: EventWriter<AdjustHp>,
+) {
+    let dt = time.delta_secs();
+    if dt <= 0.0 {
+        return;
+    }
+    for (entity, steering, mut velocity, mut transform, collider, charged_by) in steered.iter_mut()
+    {
+        if velocity.0 == Vec3::ZERO {
+            continue;
+        }
+        let charge_source = charged_by.map_or(entity, |charged_by| charged_by.0);
+        let pays = has_health.contains(charge_source);
+        // Whatever powered it is gone, so it has nothing left to move with
+        if !pays && charged_by.is_some() {
+            velocity.0 = Vec3::ZERO;
+            continue;
+        }
+
+        // The charge resizes the ball before physics catches up with its scale
+        let mut shape = collider.clone();
+        shape.set_scale(transform.scale, 10);
+        let start = transform.translation;
+        let mut position = collide_and_slide(
+            &spatial_query,
+            &shape,
+            entity,
+            transform.rotation,
+            start,
+            velocity.0 * dt,
+            steering.skin,
+            steering.max_slides,
+            |normal| {
+                velocity.0 = velocity.0.reject_from_normalized(normal);
+                normal
+            },
+        );
+        let max_height = steering.max_height.max(steering.min_height);
+        if (position.y <= steering.min_height && velocity.0.y < 0.0)
+            || (position.y >= max_height && velocity.0.y > 0.0)
+        {
+            velocity.0.y = 0.0;
+        }
+        position.y = position.y.clamp(steering.min_height, max_height);
+
+        let travelled = position.distance(start);
+        if pays && travelled > 0.0 {
+            adjust_hp_events.write(
+                AdjustHp::new(charge_source, -travelled * steering.charge_per_unit)
+                    .with_source(entity),
+            );
+        }
+        transform.translation = position;
+    }
+}
@@ -0,0 +1,119 @@
+//! Enemy waves. Each [`Wave`] in the [`WaveConfig`] spawns its enemies one at
+//! a time around the edge of the level once the one before has been spawned
+//! and cleared. The run is won once every wave is cleared.
+
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+use rand::Rng;
+use smart_default::SmartDefault;
+use std::f32::consts::TAU;
+
+use crate::game::health::Dead;
+use crate::game::pause_controller::PausableSystems;
+use crate::game::prefabs::enemy::Enemy;
+use crate::game::rng::global::GlobalRng;
+use crate::game::screens::Screen;
+
+#[derive(Debug, Clone, Reflect)]
+pub struct Wave {
+    pub enemy: Enemy,
+    pub count: u32,
+    pub spawn_interval_secs: f32,
+}
+
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, Clone, Reflect, SmartDefault)]
+#[reflect(Resource)]
+pub struct WaveConfig {
+    #[default(vec![
+        Wave { enemy: Enemy::BaseSkele, count: 5, spawn_interval_secs: 2.0 },
+        Wave { enemy: Enemy::BaseSkele, count: 10, spawn_interval_secs: 1.5 },
+        Wave { enemy: Enemy::BaseSkele, count: 20, spawn_interval_secs: 1.0 },
+    ])]
+    pub waves: Vec<Wave>,
+    /// Breather before each wave, the first included.
+    #[default(8.0)]
+    pub wave_delay_secs: f32,
+    /// How far from the centre of the level enemies spawn.
+    #[default(450.0)]
+    pub spawn_distance: f32,
+    #[default(10.0)]
+    pub spawn_height: f32,
+    #[default(15.0)]
+    pub enemy_scale: f32,
+}
+
+/// Progress through the [`WaveConfig`] this run.
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, Clone, Reflect, Default)]
+#[reflect(Resource)]
+pub struct Waves {
+    /// Index of the wave being spawned or waited for.
+    pub current: usize,
+    /// Enemies of the current wave spawned so far.
+    pub spawned: u32,
+    /// Counts down to the next wave, then to each spawn.
+    timer: Timer,
+}
+
+impl Waves {
+    pub fn all_cleared(&self, cfg: &WaveConfig) -> bool {
+        self.current >= cfg.waves.len()
+    }
+}
+
+#[auto_plugin(app=app)]
+pub(crate) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::Gameplay), reset_waves);
+    app.add_systems(
+        Update,
+        spawn_waves
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(PausableSystems),
+    );
+}
+
+fn reset_waves(mut waves: ResMut<Waves>, cfg: Res<WaveConfig>) {
+    *waves = Waves {
+        timer: Timer::from_seconds(cfg.wave_delay_secs, TimerMode::Once),
+        ..default()
+    };
+}
+
+fn spawn_waves(
+    mut commands: Commands,
+    time: Res<Time>,
+    cfg: Res<WaveConfig>,
+    mut waves: ResMut<Waves>,
+    mut rng: GlobalRng,
+    enemies: Query<(), (With<Enemy>, Without<Dead>)>,
+) {
+    let Some(wave) = cfg.waves.get(waves.current) else {
+        return;
+    };
+    if !waves.timer.tick(time.delta()).finished() {
+        return;
+    }
+    if waves.spawned < wave.count {
+        let angle = rng.rng().random_range(0.0..TAU);
+        let position = Vec3::new(angle.cos(), 0.0, angle.sin()) * cfg.spawn_distance
+            + Vec3::Y * cfg.spawn_height;
+        commands.spawn((
+            wave.enemy,
+            Transform::from_translation(position).with_scale(Vec3::splat(cfg.enemy_scale)),
+            StateScoped(Screen::Gameplay),
+        ));
+        waves.spawned += 1;
+        waves.timer = Timer::from_seconds(wave.spawn_interval_secs, TimerMode::Once);
+        return;
+    }
+    // Everything's spawned, so wait for it to be cleared
+    if enemies.is_empty() {
+        info!("wave {} cleared", waves.current + 1);
+        waves.current += 1;
+        waves.spawned = 0;
+        waves.timer = Timer::from_seconds(cfg.wave_delay_secs, TimerMode::Once);
+    }
+}